@@ -0,0 +1,74 @@
+use crate::fix;
+use crate::manifests::{Dep, Member, Root, WORKSPACE_DEPENDENCIES};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+use std::collections::BTreeSet;
+
+/// Member `workspace = true` dependencies whose `default-features = false` is ignored by cargo, as
+/// the workspace entry keeps default features on.
+pub(crate) fn ineffective_default_features<'a>(root: &Root, member: &'a Member) -> Vec<&'a Dep> {
+    member
+        .dependencies
+        .iter()
+        .filter(|dep| matches!(dep.spec.source(), Some(Source::Workspace(_))))
+        .filter(|dep| dep.spec.default_features() == Some(false))
+        .filter(|dep| {
+            root.dependency(&dep.key)
+                .is_some_and(|e| e.spec.default_features() != Some(false))
+        })
+        .collect()
+}
+
+/// Keys of workspace dependencies where `default-features = false` can be moved, as every member
+/// inheriting them disables default features.
+pub(crate) fn movable_to_workspace<'a>(root: &Root, members: &'a [Member]) -> Vec<&'a str> {
+    let keys: BTreeSet<_> = members
+        .iter()
+        .flat_map(|member| ineffective_default_features(root, member))
+        .map(|dep| dep.key.as_str())
+        .collect();
+
+    keys.into_iter()
+        .filter(|key| {
+            members
+                .iter()
+                .flat_map(|member| &member.dependencies)
+                .filter(|dep| dep.key == *key)
+                .filter(|dep| matches!(dep.spec.source(), Some(Source::Workspace(_))))
+                .all(|dep| dep.spec.default_features() == Some(false))
+        })
+        .collect()
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::IneffectiveDefaultFeatures);
+
+    for member in members {
+        for dep in ineffective_default_features(root, member) {
+            report.push(
+                Finding::new(
+                    Rule::IneffectiveDefaultFeatures,
+                    member.manifest_path(),
+                    &dep.key,
+                )
+                .detail("default features enabled by the workspace entry")
+                .suggestion(workspace_entry(root, dep))
+                .location(member.locate(dep)),
+            );
+        }
+    }
+}
+
+/// Workspace entry of `dep` disabling default features, ie `anyhow = { version = "1.0", default-features = false }`.
+fn workspace_entry(root: &Root, dep: &Dep) -> Option<String> {
+    let item = root
+        .manifest
+        .get_table(&WORKSPACE_DEPENDENCIES.map(str::to_owned))
+        .ok()?
+        .get(&dep.key)?;
+    let mut entry = fix::inline_entry(item)?;
+    entry.insert("default-features", false.into());
+    entry.fmt();
+
+    Some(format!("{} = {entry}", dep.key))
+}
@@ -0,0 +1,37 @@
+use crate::checks::hoist::inheriting;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+
+/// Workspace dependencies inherited by a single member, with that member, except the ignored ones.
+pub(crate) fn single_use_dependencies<'a>(
+    root: &'a Root,
+    members: &'a [Member],
+) -> Vec<(&'a Dep, &'a Member)> {
+    let ignored = root.config.ignored.as_deref().unwrap_or_default();
+
+    root.dependencies
+        .iter()
+        .filter(|dep| !ignored.contains(&dep.key))
+        .filter_map(|dep| {
+            let mut users = members
+                .iter()
+                .filter(|member| inheriting(member, &dep.key).next().is_some());
+            let user = users.next()?;
+
+            users.next().is_none().then_some((dep, user))
+        })
+        .collect()
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::SingleUseWorkspaceDependency);
+
+    for (dep, member) in single_use_dependencies(root, members) {
+        report.push(
+            Finding::new(Rule::SingleUseWorkspaceDependency, &root.path, &dep.key)
+                .package(Some(dep.package()))
+                .detail(member.package.name().as_str())
+                .location(root.locate(dep)),
+        );
+    }
+}
@@ -0,0 +1,60 @@
+use crate::location::Location;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+/// A version requirement, with the manifest and entry declaring it.
+type Requirement<'a> = (&'a Path, &'a Dep, &'a str, Option<Location>);
+
+/// Report crates whose version requirement differs across `[workspace.dependencies]` and members.
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::VersionDrift);
+
+    // requirements by package name
+    let mut requirements: BTreeMap<&str, Vec<Requirement<'_>>> = BTreeMap::new();
+
+    let root_dependencies = root
+        .dependencies
+        .iter()
+        .map(|dep| (root.path.as_path(), dep, root.locate(dep)));
+    let member_dependencies = members.iter().flat_map(|member| {
+        member
+            .dependencies
+            .iter()
+            .map(|dep| (member.manifest_path(), dep, member.locate(dep)))
+    });
+
+    for (manifest, dep, location) in root_dependencies.chain(member_dependencies) {
+        if let Some(version) = dep.spec.version() {
+            requirements
+                .entry(dep.package())
+                .or_default()
+                .push((manifest, dep, version, location));
+        }
+    }
+
+    for (package, requirements) in requirements {
+        let distinct: BTreeSet<_> = requirements
+            .iter()
+            .map(|(_, _, version, _)| normalize(version))
+            .collect();
+        if distinct.len() < 2 {
+            continue;
+        }
+
+        for (manifest, dep, version, location) in requirements {
+            report.push(
+                Finding::new(Rule::VersionDrift, manifest, &dep.key)
+                    .package(Some(package))
+                    .detail(version)
+                    .location(location),
+            );
+        }
+    }
+}
+
+/// `^` is the default operator of cargo requirements.
+pub(crate) fn normalize(version: &str) -> &str {
+    version.trim().trim_start_matches('^')
+}
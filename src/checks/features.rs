@@ -0,0 +1,67 @@
+use crate::fix;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+
+/// Features of member `workspace = true` dependencies already enabled by the workspace entry.
+pub(crate) fn redundant_features<'a>(
+    root: &Root,
+    member: &'a Member,
+) -> Vec<(&'a Dep, Vec<&'a str>)> {
+    member
+        .dependencies
+        .iter()
+        .filter(|dep| matches!(dep.spec.source(), Some(Source::Workspace(_))))
+        .filter_map(|dep| {
+            let workspace_features = root.dependency(&dep.key)?.spec.features.as_ref()?;
+            let features: Vec<_> = dep
+                .spec
+                .features
+                .iter()
+                .flatten()
+                .filter(|e| workspace_features.contains(*e))
+                .map(String::as_str)
+                .collect();
+
+            (!features.is_empty()).then_some((dep, features))
+        })
+        .collect()
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::RedundantFeature);
+
+    for member in members {
+        for (dep, features) in redundant_features(root, member) {
+            let features_path = [dep.key_path(), vec!["features"]].concat();
+            let suggestion = without_features(member, dep, &features);
+
+            for feature in features {
+                report.push(
+                    Finding::new(Rule::RedundantFeature, member.manifest_path(), &dep.key)
+                        .detail(feature)
+                        .suggestion(suggestion.as_ref())
+                        .location(member.spans.locate_value(&features_path, feature)),
+                );
+            }
+        }
+    }
+}
+
+/// Entry `dep` of `member` without `features`, ie `clap = { workspace = true }`.
+fn without_features(member: &Member, dep: &Dep, features: &[&str]) -> Option<String> {
+    let item = member.manifest.get_table(&dep.table).ok()?.get(&dep.key)?;
+    let mut entry = fix::inline_entry(item)?;
+
+    if let Some(array) = entry.get_mut("features").and_then(|e| e.as_array_mut()) {
+        array.retain(|e| !e.as_str().is_some_and(|e| features.contains(&e)));
+        if array.is_empty() {
+            entry.remove("features");
+        } else {
+            array.fmt();
+        }
+    }
+    entry.fmt();
+
+    Some(format!("{} = {entry}", dep.key))
+}
@@ -0,0 +1,67 @@
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::core::resolver::Resolve;
+use cargo::core::{GitReference, SourceId};
+use cargo::util::IntoUrl;
+use cargo::util::toml_mut::dependency::{GitSource, Source};
+use std::path::Path;
+
+/// Git source of `dep` when it follows a branch, or the default branch, instead of a `rev` or `tag`.
+fn unpinned(dep: &Dep) -> Option<&GitSource> {
+    let Some(Source::Git(source)) = dep.spec.source() else {
+        return None;
+    };
+
+    (source.rev.is_none() && source.tag.is_none()).then_some(source)
+}
+
+/// Commit `Cargo.lock` resolved the git dependency `dep` to.
+fn locked_commit(dep: &Dep, source: &GitSource, resolve: &Resolve) -> Option<String> {
+    let reference = match &source.branch {
+        Some(branch) => GitReference::Branch(branch.clone()),
+        None => GitReference::DefaultBranch,
+    };
+    let source_id = SourceId::for_git(&source.git.as_str().into_url().ok()?, reference).ok()?;
+
+    resolve
+        .iter()
+        .find(|e| e.name() == dep.package() && e.source_id() == source_id)
+        .and_then(|e| e.source_id().precise_git_fragment())
+        .map(str::to_owned)
+}
+
+fn finding(dep: &Dep, manifest: &Path, resolve: Option<&Resolve>) -> Option<Finding> {
+    let source = unpinned(dep)?;
+    let commit = resolve.and_then(|e| locked_commit(dep, source, e));
+
+    let mut finding = Finding::new(Rule::UnpinnedGitDependency, manifest, &dep.key)
+        .package(Some(dep.package()))
+        .suggestion(commit.as_ref().map(|e| format!("rev = \"{e}\"")));
+    // the locked commit is displayed next to the entry in text reports
+    finding.detail = commit;
+
+    Some(finding)
+}
+
+pub(crate) fn check(
+    root: &Root,
+    members: &[Member],
+    resolve: Option<&Resolve>,
+    report: &mut Report,
+) {
+    report.check(Rule::UnpinnedGitDependency);
+
+    for dep in &root.dependencies {
+        if let Some(finding) = finding(dep, &root.path, resolve) {
+            report.push(finding.location(root.locate(dep)));
+        }
+    }
+
+    for member in members {
+        for dep in &member.dependencies {
+            if let Some(finding) = finding(dep, member.manifest_path(), resolve) {
+                report.push(finding.location(member.locate(dep)));
+            }
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+
+/// Dependencies of the member inheriting the `[workspace.dependencies]` entry `key`.
+pub(crate) fn inheriting<'a>(member: &'a Member, key: &str) -> impl Iterator<Item = &'a Dep> {
+    member
+        .dependencies
+        .iter()
+        .filter(move |dep| dep.key == key)
+        .filter(|dep| matches!(dep.spec.source(), Some(Source::Workspace(_))))
+}
+
+/// Features added by every member inheriting a workspace dependency, which could be enabled by the
+/// `[workspace.dependencies]` entry instead.
+pub(crate) fn hoistable_features<'a>(
+    root: &'a Root,
+    members: &'a [Member],
+) -> Vec<(&'a Dep, Vec<&'a str>)> {
+    root.dependencies
+        .iter()
+        .filter_map(|workspace_dep| {
+            let mut usages = members
+                .iter()
+                .flat_map(|member| inheriting(member, &workspace_dep.key));
+
+            // features already enabled by the workspace entry are reported as redundant
+            let workspace_features = workspace_dep.spec.features.iter().flatten();
+            let mut common: Vec<_> = usages
+                .next()?
+                .spec
+                .features
+                .iter()
+                .flatten()
+                .filter(|e| !workspace_features.clone().any(|f| f == *e))
+                .map(String::as_str)
+                .collect();
+            for dep in usages {
+                common.retain(|e| dep.spec.features.iter().flatten().any(|f| f == e));
+            }
+
+            (!common.is_empty()).then_some((workspace_dep, common))
+        })
+        .collect()
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::HoistableFeature);
+
+    for (dep, features) in hoistable_features(root, members) {
+        for feature in features {
+            report.push(
+                Finding::new(Rule::HoistableFeature, &root.path, &dep.key)
+                    .package(Some(dep.package()))
+                    .detail(feature)
+                    .location(root.locate(dep)),
+            );
+        }
+    }
+}
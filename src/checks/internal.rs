@@ -0,0 +1,92 @@
+use crate::fix;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+use std::fs;
+use std::path::Path;
+
+/// Member the `path` dependency `dep` points to.
+pub(crate) fn path_target<'a>(dep: &Dep, members: &'a [Member]) -> Option<&'a Member> {
+    let Some(Source::Path(source)) = dep.spec.source() else {
+        return None;
+    };
+    let path = canonicalize(&source.path);
+
+    members
+        .iter()
+        .find(|member| member.manifest_path().parent().map(canonicalize) == Some(path.clone()))
+}
+
+/// Member dependencies on other members by `path` instead of `workspace = true`, with their target.
+pub(crate) fn internal_path_dependencies<'a>(
+    member: &'a Member,
+    members: &'a [Member],
+) -> Vec<(&'a Dep, &'a Member)> {
+    member
+        .dependencies
+        .iter()
+        .filter_map(|dep| Some((dep, path_target(dep, members)?)))
+        .collect()
+}
+
+/// Entries of `[workspace.dependencies]` pointing to a member without `version`, with their target.
+pub(crate) fn unversioned_workspace_dependencies<'a>(
+    root: &'a Root,
+    members: &'a [Member],
+) -> Vec<(&'a Dep, &'a Member)> {
+    root.dependencies
+        .iter()
+        .filter(|dep| dep.spec.version().is_none())
+        .filter_map(|dep| Some((dep, path_target(dep, members)?)))
+        .collect()
+}
+
+/// `dep` pointing to its `target` member with a version, as expected in `[workspace.dependencies]`.
+pub(crate) fn versioned(dep: &Dep, target: &Member) -> Dep {
+    let mut dep = dep.clone();
+    if let Some(Source::Path(source)) = &mut dep.spec.source {
+        // resolved, as the workspace entry is relative to the root manifest
+        if let Some(path) = target.manifest_path().parent() {
+            source.path = path.to_path_buf();
+        }
+        if source.version.is_none() {
+            source.version = Some(target.package.version().to_string());
+        }
+    }
+
+    dep
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::InternalPathDependency);
+    report.check(Rule::UnversionedInternalDependency);
+
+    for member in members {
+        for (dep, _) in internal_path_dependencies(member, members) {
+            report.push(
+                Finding::new(
+                    Rule::InternalPathDependency,
+                    member.manifest_path(),
+                    &dep.key,
+                )
+                .package(Some(dep.package()))
+                .suggestion(Some(fix::inheriting_entry(&dep.key, &dep.spec)))
+                .location(member.locate(dep)),
+            );
+        }
+    }
+
+    for (dep, target) in unversioned_workspace_dependencies(root, members) {
+        report.push(
+            Finding::new(Rule::UnversionedInternalDependency, &root.path, &dep.key)
+                .package(Some(dep.package()))
+                .suggestion(Some(target.package.version().to_string()))
+                .location(root.locate(dep)),
+        );
+    }
+}
+
+/// Paths of dependencies hold `..` components, so they are compared once resolved.
+pub(crate) fn canonicalize(path: &Path) -> std::path::PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
@@ -0,0 +1,56 @@
+use crate::manifests::{Member, Root};
+use crate::report::{Finding, Report, Rule};
+
+/// Whether the root manifest declares `[workspace.lints]`.
+pub(crate) fn has_workspace_lints(root: &Root) -> bool {
+    root.manifest
+        .data
+        .get("workspace")
+        .and_then(|e| e.get("lints"))
+        .is_some()
+}
+
+/// Whether the member has no `[lints]` table, so that inheritance can be inserted.
+pub(crate) fn misses_lints(member: &Member) -> bool {
+    member.manifest.data.get("lints").is_none()
+}
+
+/// Lints table of members inheriting `[workspace.lints]`.
+const INHERITED_LINTS: &str = "[lints] workspace = true";
+
+/// Report members not inheriting `[workspace.lints]`, either lacking `[lints]` or defining
+/// their own lints.
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    if !has_workspace_lints(root) {
+        return;
+    }
+    report.check(Rule::NonInheritedLints);
+
+    for member in members {
+        let Some(lints) = member.manifest.data.get("lints") else {
+            report.push(
+                Finding::new(Rule::NonInheritedLints, member.manifest_path(), "lints")
+                    .suggestion(Some(INHERITED_LINTS))
+                    .location(member.spans.locate(&["package"])),
+            );
+            continue;
+        };
+
+        if lints.get("workspace").and_then(|e| e.as_bool()) == Some(true) {
+            continue;
+        }
+
+        // one finding per tool, ie `[lints.clippy]`
+        for (tool, _) in lints.as_table_like().into_iter().flat_map(|e| e.iter()) {
+            report.push(
+                Finding::new(
+                    Rule::NonInheritedLints,
+                    member.manifest_path(),
+                    format!("lints.{tool}"),
+                )
+                .suggestion(Some(INHERITED_LINTS))
+                .location(member.spans.locate(&["lints", tool])),
+            );
+        }
+    }
+}
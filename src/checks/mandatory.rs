@@ -0,0 +1,77 @@
+use crate::checks::internal;
+use crate::config::SourceKind;
+use crate::fix;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+
+/// Rule reporting dependencies of the source `kind` not using `workspace = true`.
+pub(crate) fn rule(kind: SourceKind) -> Rule {
+    match kind {
+        SourceKind::Registry => Rule::NonWorkspaceDependency,
+        SourceKind::AlternateRegistry => Rule::NonWorkspaceAlternateRegistryDependency,
+        SourceKind::Git => Rule::NonWorkspaceGitDependency,
+        SourceKind::Path => Rule::NonWorkspacePathDependency,
+    }
+}
+
+/// Kind of source of `dep`, `None` for workspace dependencies and members.
+fn source_kind(dep: &Dep, members: &[Member]) -> Option<SourceKind> {
+    match dep.spec.source()? {
+        Source::Registry(_) if dep.spec.registry().is_some() => Some(SourceKind::AlternateRegistry),
+        Source::Registry(_) => Some(SourceKind::Registry),
+        Source::Git(_) => Some(SourceKind::Git),
+        // members are reported as internal path dependencies
+        Source::Path(_) if internal::path_target(dep, members).is_some() => None,
+        Source::Path(_) => Some(SourceKind::Path),
+        Source::Workspace(_) => None,
+    }
+}
+
+/// Dependencies of the member from the configured sources not using `workspace = true`, except
+/// the allowed ones.
+pub(crate) fn non_workspace_dependencies<'a>(
+    root: &Root,
+    member: &'a Member,
+    members: &[Member],
+) -> Vec<(&'a Dep, SourceKind)> {
+    let kinds = root.config.mandatory_sources();
+
+    member
+        .dependencies
+        .iter()
+        .filter_map(|dep| Some((dep, source_kind(dep, members)?)))
+        .filter(|(_, kind)| kinds.contains(kind))
+        .filter(|(dep, _)| !member.config.allows_non_workspace(&dep.key, dep.package()))
+        .collect()
+}
+
+/// `dep` with a resolved path, as the workspace entry is relative to the root manifest.
+pub(crate) fn resolved(dep: &Dep) -> Dep {
+    let mut dep = dep.clone();
+    if let Some(Source::Path(source)) = &mut dep.spec.source {
+        source.path = internal::canonicalize(&source.path);
+    }
+
+    dep
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    let kinds = root.config.mandatory_sources();
+    for (_, kind) in SourceKind::VARIANTS {
+        if kinds.contains(&kind) {
+            report.check(rule(kind));
+        }
+    }
+
+    for member in members {
+        for (dep, kind) in non_workspace_dependencies(root, member, members) {
+            report.push(
+                Finding::new(rule(kind), member.manifest_path(), &dep.key)
+                    .package(Some(dep.package()))
+                    .suggestion(Some(fix::inheriting_entry(&dep.key, &dep.spec)))
+                    .location(member.locate(dep)),
+            );
+        }
+    }
+}
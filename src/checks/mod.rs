@@ -0,0 +1,13 @@
+pub(crate) mod default_features;
+pub(crate) mod demote;
+pub(crate) mod drift;
+pub(crate) mod features;
+pub(crate) mod git;
+pub(crate) mod hoist;
+pub(crate) mod internal;
+pub(crate) mod lints;
+pub(crate) mod mandatory;
+pub(crate) mod package;
+pub(crate) mod patch;
+pub(crate) mod promote;
+pub(crate) mod unused;
@@ -0,0 +1,39 @@
+use crate::manifests::{Member, Root, WORKSPACE_PACKAGE};
+use crate::report::{Finding, Report, Rule};
+
+/// Report `[workspace.package]` fields inherited by no member.
+pub(crate) fn check_unused(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::UnusedWorkspacePackageField);
+
+    for key in root.package_fields() {
+        if members.iter().any(|e| e.inherits_package_field(key)) {
+            continue;
+        }
+
+        let key_path = [WORKSPACE_PACKAGE.as_slice(), &[key]].concat();
+        report.push(
+            Finding::new(Rule::UnusedWorkspacePackageField, &root.path, key)
+                .location(root.spans.locate(&key_path)),
+        );
+    }
+}
+
+/// Report member `[package]` fields set locally while `[workspace.package]` declares them.
+pub(crate) fn check_mandatory(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::NonInheritedPackageField);
+
+    let fields = root.package_fields();
+    for member in members {
+        for key in &fields {
+            if member.package_field(key).is_none() || member.inherits_package_field(key) {
+                continue;
+            }
+
+            report.push(
+                Finding::new(Rule::NonInheritedPackageField, member.manifest_path(), *key)
+                    .suggestion(Some(format!("{key}.workspace = true")))
+                    .location(member.spans.locate(&["package", key])),
+            );
+        }
+    }
+}
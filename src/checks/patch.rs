@@ -0,0 +1,148 @@
+use crate::checks::internal::canonicalize;
+use crate::manifests::Root;
+use crate::report::{Finding, Report, Rule};
+use cargo::core::resolver::Resolve;
+use cargo::core::{GitReference, PackageIdSpec, PackageIdSpecQuery, SourceId};
+use cargo::util::IntoUrl;
+use log::debug;
+
+/// An entry of the `[patch.<registry>]` or `[replace]` tables of the root manifest.
+#[derive(Clone, Debug)]
+pub(crate) struct Override {
+    /// path of the table holding the entry, ie `["patch", "crates-io"]`
+    pub(crate) table: Vec<String>,
+    /// key of the entry, a package name for patches and a package id spec for replacements
+    pub(crate) key: String,
+    /// package name of a patch, which differs from the key when renamed
+    pub(crate) package: String,
+    /// `git` or `path` source of the entry, `None` for other sources
+    pub(crate) source: Option<SourceId>,
+}
+
+impl Override {
+    pub(crate) fn key_path(&self) -> Vec<&str> {
+        self.table
+            .iter()
+            .map(String::as_str)
+            .chain([self.key.as_str()])
+            .collect()
+    }
+}
+
+fn entries(root: &Root, table: &[&str]) -> Vec<Override> {
+    let mut item = root.manifest.data.as_item();
+    for key in table {
+        let Some(e) = item.get(key) else {
+            return vec![];
+        };
+        item = e;
+    }
+
+    item.as_table_like()
+        .into_iter()
+        .flat_map(|e| e.iter())
+        .map(|(key, item)| Override {
+            table: table.iter().map(|e| (*e).to_owned()).collect(),
+            key: key.to_owned(),
+            package: item
+                .get("package")
+                .and_then(|e| e.as_str())
+                .unwrap_or(key)
+                .to_owned(),
+            source: source(root, item),
+        })
+        .collect()
+}
+
+/// Source of the entry `item`, as recorded in `Cargo.lock`.
+fn source(root: &Root, item: &toml_edit::Item) -> Option<SourceId> {
+    let value = |key| item.get(key).and_then(|e| e.as_str());
+
+    if let Some(git) = value("git") {
+        let reference = match (value("branch"), value("tag"), value("rev")) {
+            (Some(branch), _, _) => GitReference::Branch(branch.to_owned()),
+            (_, Some(tag), _) => GitReference::Tag(tag.to_owned()),
+            (_, _, Some(rev)) => GitReference::Rev(rev.to_owned()),
+            _ => GitReference::DefaultBranch,
+        };
+        return SourceId::for_git(&git.into_url().ok()?, reference).ok();
+    }
+
+    let path = root.path.parent()?.join(value("path")?);
+    SourceId::for_path(&canonicalize(&path)).ok()
+}
+
+/// Whether the root manifest declares `[patch]` or `[replace]` entries.
+pub(crate) fn declares_overrides(root: &Root) -> bool {
+    ["patch", "replace"]
+        .iter()
+        .any(|e| root.manifest.data.contains_key(e))
+}
+
+/// Entries of the `[patch.<registry>]` tables that `Cargo.lock` lists as unused.
+pub(crate) fn unused_patches(root: &Root, resolve: &Resolve) -> Vec<Override> {
+    entries(root, &["patch"])
+        .iter()
+        .flat_map(|registry| entries(root, &["patch", &registry.key]))
+        // cargo keeps a patch unused when the crate is not in the graph or its version does not match
+        .filter(|entry| {
+            resolve.unused_patches().iter().any(|e| {
+                // the same crate can be patched for several registries
+                e.name() == entry.package.as_str()
+                    && entry.source.is_none_or(|source| e.source_id() == source)
+            })
+        })
+        .collect()
+}
+
+/// Entries of `[replace]` matching no replaced package of `Cargo.lock`.
+pub(crate) fn unused_replacements(root: &Root, resolve: &Resolve) -> Vec<Override> {
+    entries(root, &["replace"])
+        .into_iter()
+        .filter(|entry| {
+            let spec = match PackageIdSpec::parse(&entry.key) {
+                Ok(spec) => spec,
+                Err(err) => {
+                    debug!("Skipping replacement `{}` : {err}", entry.key);
+                    return false;
+                }
+            };
+
+            !resolve.replacements().keys().any(|e| spec.matches(*e))
+        })
+        .collect()
+}
+
+/// `Cargo.lock` being required to know which entries are applied, nothing is checked without it.
+pub(crate) fn check(root: &Root, resolve: Option<&Resolve>, report: &mut Report) {
+    let Some(resolve) = resolve else {
+        if declares_overrides(root) {
+            debug!("No Cargo.lock, skipping `[patch]` and `[replace]` entries");
+        }
+        return;
+    };
+
+    if root.manifest.data.contains_key("patch") {
+        report.check(Rule::UnusedPatch);
+
+        for entry in unused_patches(root, resolve) {
+            report.push(
+                Finding::new(Rule::UnusedPatch, &root.path, &entry.key)
+                    .package(Some(&entry.package))
+                    .detail(&entry.table[1])
+                    .location(root.spans.locate(&entry.key_path())),
+            );
+        }
+    }
+
+    if root.manifest.data.contains_key("replace") {
+        report.check(Rule::UnusedReplace);
+
+        for entry in unused_replacements(root, resolve) {
+            report.push(
+                Finding::new(Rule::UnusedReplace, &root.path, &entry.key)
+                    .location(root.spans.locate(&entry.key_path())),
+            );
+        }
+    }
+}
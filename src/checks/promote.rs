@@ -0,0 +1,93 @@
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+use cargo::util::toml_mut::dependency::Source;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Default minimum number of members declaring a crate for it to be reported.
+const DEFAULT_THRESHOLD: usize = 2;
+
+/// Report registry crates declared by several members while missing from
+/// `[workspace.dependencies]`, with a requirement satisfying all of them when possible.
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::SharedDependency);
+
+    let threshold = root.config.promote_threshold.unwrap_or(DEFAULT_THRESHOLD);
+
+    // member dependencies by package name
+    let mut declarations: BTreeMap<&str, Vec<(&Member, &Dep)>> = BTreeMap::new();
+    for member in members {
+        for dep in &member.dependencies {
+            if matches!(dep.spec.source(), Some(Source::Registry(_)))
+                && !root
+                    .dependencies
+                    .iter()
+                    .any(|e| e.package() == dep.package())
+            {
+                declarations
+                    .entry(dep.package())
+                    .or_default()
+                    .push((member, dep));
+            }
+        }
+    }
+
+    for (package, declarations) in declarations {
+        let declaring: BTreeSet<_> = declarations
+            .iter()
+            .map(|(member, _)| member.manifest_path())
+            .collect();
+        if declaring.len() < threshold {
+            continue;
+        }
+
+        let suggestion = unified_requirement(declarations.iter().map(|(_, dep)| dep))
+            .map(|version| format!("{package} = \"{version}\""));
+
+        for (member, dep) in declarations {
+            report.push(
+                Finding::new(Rule::SharedDependency, member.manifest_path(), &dep.key)
+                    .package(Some(package))
+                    .detail(dep.spec.version().unwrap_or("*"))
+                    .suggestion(suggestion.as_deref())
+                    .location(member.locate(dep)),
+            );
+        }
+    }
+}
+
+/// The highest of the minimum versions required, when all requirements are caret ones of the same
+/// compatible series, ie `1.0.200` for `1.0.200` and `1.0`.
+fn unified_requirement<'a>(dependencies: impl Iterator<Item = &'a &'a Dep>) -> Option<&'a str> {
+    let versions = dependencies
+        .map(|dep| {
+            let version = dep.spec.version()?.trim();
+            let version = version.strip_prefix('^').unwrap_or(version);
+            Some((minimum_version(version)?, version))
+        })
+        .collect::<Option<Vec<_>>>()?;
+
+    let series: BTreeSet<_> = versions.iter().map(|(e, _)| compatible_series(e)).collect();
+    if series.len() != 1 {
+        return None;
+    }
+
+    versions.into_iter().max().map(|(_, version)| version)
+}
+
+/// Numeric components of a plain requirement like `1.0.200`, `None` for other operators.
+fn minimum_version(version: &str) -> Option<Vec<u64>> {
+    version
+        .split('.')
+        .map(|e| e.parse().ok())
+        .collect::<Option<Vec<_>>>()
+        .filter(|e| e.len() <= 3)
+}
+
+/// Components up to the leftmost non-zero one, which caret requirements keep unchanged.
+fn compatible_series(version: &[u64]) -> &[u64] {
+    let end = version
+        .iter()
+        .position(|e| *e != 0)
+        .map_or(version.len(), |e| e + 1);
+    &version[..end]
+}
@@ -0,0 +1,56 @@
+use crate::config::WORKSPACE_METADATA;
+use crate::manifests::{Dep, Member, Root};
+use crate::report::{Finding, Report, Rule};
+
+/// Entries of `[workspace.dependencies]` used by no member, except the ignored ones.
+pub(crate) fn unused_workspace_dependencies<'a>(
+    root: &'a Root,
+    members: &[Member],
+) -> Vec<&'a Dep> {
+    let ignored = root.config.ignored.as_deref().unwrap_or_default();
+
+    let mut unused: Vec<_> = root
+        .dependencies
+        .iter()
+        .filter(|dep| !ignored.contains(&dep.key))
+        // workspace dependencies are matched by key, as they can be renamed
+        .filter(|dep| {
+            !members.iter().any(|member| {
+                member
+                    .package
+                    .dependencies()
+                    .iter()
+                    .any(|e| e.name_in_toml().as_str() == dep.key)
+            })
+        })
+        .collect();
+    unused.sort_by(|a, b| a.key.cmp(&b.key));
+
+    unused
+}
+
+pub(crate) fn check(root: &Root, members: &[Member], report: &mut Report) {
+    report.check(Rule::UnusedWorkspaceDependency);
+
+    for dep in unused_workspace_dependencies(root, members) {
+        report.push(
+            Finding::new(Rule::UnusedWorkspaceDependency, &root.path, &dep.key)
+                .package(Some(dep.package()))
+                .location(root.locate(dep)),
+        );
+    }
+
+    if let Some(ignored) = &root.config.ignored {
+        report.check(Rule::StaleIgnore);
+
+        let ignored_path = [WORKSPACE_METADATA.as_slice(), &["ignored"]].concat();
+        for dep in ignored {
+            if root.dependency(dep).is_none() {
+                report.push(
+                    Finding::new(Rule::StaleIgnore, &root.path, dep)
+                        .location(root.spans.locate_value(&ignored_path, dep)),
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,186 @@
+use anyhow::anyhow;
+use cargo::CargoResult;
+use cargo::util::toml_mut::manifest::LocalManifest;
+
+/// Path of the cargo-neat settings in the root manifest.
+pub(crate) const WORKSPACE_METADATA: [&str; 3] = ["workspace", "metadata", "cargo-neat"];
+
+/// Path of the cargo-neat settings in a member manifest.
+pub(crate) const PACKAGE_METADATA: [&str; 3] = ["package", "metadata", "cargo-neat"];
+
+/// Settings read from `[workspace.metadata.cargo-neat]`.
+#[derive(Debug, Default)]
+pub(crate) struct WorkspaceConfig {
+    /// workspace dependencies excluded from the unused check, `None` when not configured
+    pub(crate) ignored: Option<Vec<String>>,
+    /// minimum number of members declaring a crate for it to be worth a workspace dependency
+    pub(crate) promote_threshold: Option<usize>,
+    /// sources of the dependencies required to be workspace ones with `-m`, `None` when not configured
+    pub(crate) mandatory_sources: Option<Vec<SourceKind>>,
+}
+
+impl WorkspaceConfig {
+    pub(crate) fn read(root_manifest: &LocalManifest) -> CargoResult<Self> {
+        let Ok(table) = root_manifest.get_table(&WORKSPACE_METADATA.map(str::to_owned)) else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            ignored: string_array(root_manifest, table, &WORKSPACE_METADATA, "ignored")?,
+            promote_threshold: threshold(
+                root_manifest,
+                table,
+                &WORKSPACE_METADATA,
+                "promote-threshold",
+            )?,
+            mandatory_sources: source_kinds(
+                root_manifest,
+                table,
+                &WORKSPACE_METADATA,
+                "mandatory-sources",
+            )?,
+        })
+    }
+
+    /// Sources checked by `-m`, registries by default.
+    pub(crate) fn mandatory_sources(&self) -> &[SourceKind] {
+        self.mandatory_sources
+            .as_deref()
+            .unwrap_or(&[SourceKind::Registry, SourceKind::AlternateRegistry])
+    }
+}
+
+/// Kind of source of a dependency.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SourceKind {
+    /// crates.io
+    Registry,
+    /// registry set with `registry = "..."`
+    AlternateRegistry,
+    Git,
+    /// `path` outside of the workspace members
+    Path,
+}
+
+impl SourceKind {
+    pub(crate) const VARIANTS: [(&'static str, SourceKind); 4] = [
+        ("registry", SourceKind::Registry),
+        ("alternate-registry", SourceKind::AlternateRegistry),
+        ("git", SourceKind::Git),
+        ("path", SourceKind::Path),
+    ];
+}
+
+/// Settings read from `[package.metadata.cargo-neat]` of a member.
+#[derive(Debug, Default)]
+pub(crate) struct MemberConfig {
+    /// dependencies allowed not to use `workspace = true`, by key or package name
+    pub(crate) allow_non_workspace: Vec<String>,
+}
+
+impl MemberConfig {
+    pub(crate) fn read(member_manifest: &LocalManifest) -> CargoResult<Self> {
+        let Ok(table) = member_manifest.get_table(&PACKAGE_METADATA.map(str::to_owned)) else {
+            return Ok(Self::default());
+        };
+
+        Ok(Self {
+            allow_non_workspace: string_array(
+                member_manifest,
+                table,
+                &PACKAGE_METADATA,
+                "allow-non-workspace",
+            )?
+            .unwrap_or_default(),
+        })
+    }
+
+    pub(crate) fn allows_non_workspace(&self, key: &str, package: &str) -> bool {
+        self.allow_non_workspace
+            .iter()
+            .any(|e| e == key || e == package)
+    }
+}
+
+/// Read `key` of `table` as an array of strings.
+fn string_array(
+    manifest: &LocalManifest,
+    table: &toml_edit::Item,
+    table_path: &[&str],
+    key: &str,
+) -> CargoResult<Option<Vec<String>>> {
+    let Some(item) = table.get(key) else {
+        return Ok(None);
+    };
+
+    item.as_array()
+        .and_then(|e| {
+            e.iter()
+                .map(|e| e.as_str().map(str::to_owned))
+                .collect::<Option<Vec<_>>>()
+        })
+        .map(Some)
+        .ok_or_else(|| {
+            anyhow!(
+                "`{}.{key}` must be an array of strings in `{}`",
+                table_path.join("."),
+                manifest.path.display()
+            )
+        })
+}
+
+/// Read `key` of `table` as an integer of at least 2.
+fn threshold(
+    manifest: &LocalManifest,
+    table: &toml_edit::Item,
+    table_path: &[&str],
+    key: &str,
+) -> CargoResult<Option<usize>> {
+    let Some(item) = table.get(key) else {
+        return Ok(None);
+    };
+
+    item.as_integer()
+        .and_then(|e| usize::try_from(e).ok())
+        .filter(|e| *e >= 2)
+        .map(Some)
+        .ok_or_else(|| {
+            anyhow!(
+                "`{}.{key}` must be an integer of at least 2 in `{}`",
+                table_path.join("."),
+                manifest.path.display()
+            )
+        })
+}
+
+/// Read `key` of `table` as an array of source kinds.
+fn source_kinds(
+    manifest: &LocalManifest,
+    table: &toml_edit::Item,
+    table_path: &[&str],
+    key: &str,
+) -> CargoResult<Option<Vec<SourceKind>>> {
+    let Some(names) = string_array(manifest, table, table_path, key)? else {
+        return Ok(None);
+    };
+
+    names
+        .iter()
+        .map(|name| {
+            SourceKind::VARIANTS
+                .iter()
+                .find(|(e, _)| e == name)
+                .map(|(_, kind)| *kind)
+                .ok_or_else(|| {
+                    let names: Vec<_> = SourceKind::VARIANTS.iter().map(|(e, _)| *e).collect();
+                    anyhow!(
+                        "unknown source `{name}` in `{}.{key}` of `{}`, expected one of: {}",
+                        table_path.join("."),
+                        manifest.path.display(),
+                        names.join(", ")
+                    )
+                })
+        })
+        .collect::<CargoResult<_>>()
+        .map(Some)
+}
@@ -0,0 +1,527 @@
+use cargo::CargoResult;
+use cargo::core::{Features, Workspace};
+use cargo::util::toml_mut::dependency::{Dependency, Source, WorkspaceSource};
+use cargo::util::toml_mut::manifest::LocalManifest;
+
+use crate::checks::drift::normalize;
+use crate::checks::internal::canonicalize;
+use crate::checks::patch::Override;
+use crate::manifests::{Dep, WORKSPACE_DEPENDENCIES};
+
+fn workspace_dependencies_path() -> Vec<String> {
+    WORKSPACE_DEPENDENCIES.map(str::to_owned).to_vec()
+}
+
+/// Remove `dependencies` from the `[workspace.dependencies]` table of the root manifest.
+///
+/// The manifest is edited in place, so formatting and comments of the remaining entries are kept.
+pub(crate) fn remove_workspace_dependencies(
+    root_manifest: &mut LocalManifest,
+    dependencies: &[String],
+) -> CargoResult<()> {
+    let table_path = workspace_dependencies_path();
+
+    for dep in dependencies {
+        root_manifest.remove_from_table(&table_path, dep)?;
+    }
+
+    Ok(())
+}
+
+/// Remove `[patch.<registry>]` and `[replace]` entries from the root manifest.
+///
+/// Tables left empty are removed as well.
+pub(crate) fn remove_overrides(
+    root_manifest: &mut LocalManifest,
+    overrides: &[Override],
+) -> CargoResult<()> {
+    for entry in overrides {
+        root_manifest.remove_from_table(&entry.table, &entry.key)?;
+
+        // `[patch.<registry>]` then `[patch]`, or `[replace]`
+        for depth in (1..=entry.table.len()).rev() {
+            let table = &entry.table[..depth];
+            let is_empty = root_manifest
+                .get_table(table)
+                .ok()
+                .and_then(|e| e.as_table_like())
+                .is_some_and(|e| e.is_empty());
+            if !is_empty {
+                break;
+            }
+            match table.split_last() {
+                Some((key, [])) => {
+                    root_manifest.data.remove(key);
+                }
+                Some((key, parent)) => root_manifest.remove_from_table(parent, key)?,
+                None => {}
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Rewrite member `dependencies` to `{ workspace = true, ... }`.
+///
+/// A missing `[workspace.dependencies]` entry is added to the root manifest, an existing one is
+/// reused when it points to the same package from the same source with the same version
+/// requirement.
+/// Added entries disable default features when they are listed in `no_default_features`, the
+/// keys disabling them in any member. Member-local keys (`features`, `optional`, `public`) are
+/// kept.
+///
+/// `member_manifest` is `None` when the member is the root package, which lives in the root
+/// manifest.
+///
+/// Returns the keys of the dependencies that could not be migrated, with the reason.
+pub(crate) fn inherit_workspace_dependencies(
+    workspace: &Workspace<'_>,
+    root_manifest: &mut LocalManifest,
+    mut member_manifest: Option<&mut LocalManifest>,
+    dependencies: &[Dep],
+    no_default_features: &[String],
+) -> CargoResult<Vec<(String, String)>> {
+    let gctx = workspace.gctx();
+    let workspace_root = workspace.root();
+    let features = Features::default();
+    let crate_root = member_manifest
+        .as_deref()
+        .unwrap_or(root_manifest)
+        .path
+        .parent()
+        .expect("manifest path is absolute")
+        .to_owned();
+
+    let mut skipped = vec![];
+
+    for Dep {
+        key,
+        table: table_path,
+        spec: dep,
+    } in dependencies
+    {
+        let ws_dep = match workspace_dependency(workspace, root_manifest, key)? {
+            Some(ws_dep) => ws_dep,
+            None => {
+                let default_features = !no_default_features.contains(key);
+                add_workspace_dependency(workspace, root_manifest, key, dep, default_features)?;
+                workspace_dependency(workspace, root_manifest, key)?
+                    .expect("workspace dependency was just added")
+            }
+        };
+
+        if let Some(reason) = mismatch(dep, &ws_dep) {
+            skipped.push((key.clone(), reason));
+            continue;
+        }
+
+        // member `default-features` only matters when it turns default features back on
+        let ws_default_features = ws_dep.default_features().unwrap_or(true);
+        let default_features = match (dep.default_features().unwrap_or(true), ws_default_features) {
+            (true, false) => Some(true),
+            (false, true) => {
+                let reason =
+                    "disables default features, enabled by the `[workspace.dependencies]` entry";
+                skipped.push((key.clone(), reason.to_owned()));
+                continue;
+            }
+            _ => None,
+        };
+
+        let mut member_dep = Dependency::new(&dep.name).set_source(WorkspaceSource::new());
+        member_dep.features = dep.features.clone();
+        member_dep.optional = dep.optional;
+        member_dep.public = dep.public;
+        member_dep.default_features = default_features;
+
+        let member_manifest = match member_manifest.as_deref_mut() {
+            Some(member_manifest) => member_manifest,
+            None => &mut *root_manifest,
+        };
+        let table = member_manifest.get_table_mut(table_path)?;
+
+        let Some((mut dep_key, dep_item)) = table
+            .as_table_like_mut()
+            .and_then(|table| table.get_key_value_mut(key))
+        else {
+            continue;
+        };
+
+        if let Some(value) = dep_item.as_value() {
+            // inline tables can't hold comments, so only the surrounding decor is worth keeping
+            let mut inherited = inherited_dependency(&member_dep);
+            *inherited.decor_mut() = value.decor().clone();
+            *dep_item = toml_edit::Item::Value(inherited.into());
+        } else {
+            member_dep.update_toml(
+                gctx,
+                workspace_root,
+                &crate_root,
+                &features,
+                &mut dep_key,
+                dep_item,
+            )?;
+        }
+    }
+
+    Ok(skipped)
+}
+
+/// Why the member dependency `dep` differs from the `[workspace.dependencies]` entry `ws_dep`.
+fn mismatch(dep: &Dependency, ws_dep: &Dependency) -> Option<String> {
+    if ws_dep.name != dep.name {
+        return Some(format!(
+            "is `{}` while the `[workspace.dependencies]` entry is `{}`",
+            dep.name, ws_dep.name
+        ));
+    }
+
+    if !same_source(dep, ws_dep) {
+        return Some(format!(
+            "comes from {} while the `[workspace.dependencies]` entry comes from {}",
+            source_name(dep),
+            source_name(ws_dep)
+        ));
+    }
+
+    let version = dep.version()?;
+    match ws_dep.version() {
+        Some(ws_version) if normalize(version) == normalize(ws_version) => None,
+        Some(ws_version) => Some(format!(
+            "requires `{version}` while the `[workspace.dependencies]` entry requires `{ws_version}`"
+        )),
+        None => Some(format!(
+            "requires `{version}` while the `[workspace.dependencies]` entry has no version"
+        )),
+    }
+}
+
+fn same_source(dep: &Dependency, ws_dep: &Dependency) -> bool {
+    match (dep.source(), ws_dep.source()) {
+        (Some(Source::Git(a)), Some(Source::Git(b))) => {
+            (&a.git, &a.branch, &a.tag, &a.rev) == (&b.git, &b.branch, &b.tag, &b.rev)
+        }
+        (Some(Source::Path(a)), Some(Source::Path(b))) => {
+            canonicalize(&a.path) == canonicalize(&b.path)
+        }
+        (Some(Source::Git(_) | Source::Path(_)), _)
+        | (_, Some(Source::Git(_) | Source::Path(_))) => false,
+        _ => dep.registry() == ws_dep.registry(),
+    }
+}
+
+/// Git url, path or registry of `dep`, as printed in messages.
+fn source_name(dep: &Dependency) -> String {
+    match dep.source() {
+        Some(source @ (Source::Git(_) | Source::Path(_))) => format!("`{source}`"),
+        _ => match dep.registry() {
+            Some(registry) => format!("registry `{registry}`"),
+            None => "crates.io".to_owned(),
+        },
+    }
+}
+
+/// Set `default-features = false` on the `[workspace.dependencies]` entry `key`.
+pub(crate) fn disable_workspace_default_features(
+    root_manifest: &mut LocalManifest,
+    key: &str,
+) -> CargoResult<()> {
+    edit_workspace_entry(root_manifest, key, |entry| {
+        entry.insert("default-features", toml_edit::value(false));
+    })
+}
+
+/// Set `version` on the `[workspace.dependencies]` entry `key`.
+pub(crate) fn set_workspace_version(
+    root_manifest: &mut LocalManifest,
+    key: &str,
+    version: &str,
+) -> CargoResult<()> {
+    edit_workspace_entry(root_manifest, key, |entry| {
+        entry.insert("version", toml_edit::value(version));
+    })
+}
+
+/// Add `features` to the `[workspace.dependencies]` entry `key`.
+pub(crate) fn add_workspace_features(
+    root_manifest: &mut LocalManifest,
+    key: &str,
+    features: &[String],
+) -> CargoResult<()> {
+    edit_workspace_entry(root_manifest, key, |entry| {
+        if !entry.contains_key("features") {
+            entry.insert("features", toml_edit::value(toml_edit::Array::new()));
+        }
+        if let Some(array) = entry.get_mut("features").and_then(|e| e.as_array_mut()) {
+            array.extend(features);
+            array.fmt();
+        }
+    })
+}
+
+/// Edit the `[workspace.dependencies]` entry `key` as a table, so that keys can be added.
+fn edit_workspace_entry(
+    root_manifest: &mut LocalManifest,
+    key: &str,
+    edit: impl FnOnce(&mut dyn toml_edit::TableLike),
+) -> CargoResult<()> {
+    let table = root_manifest.get_table_mut(&workspace_dependencies_path())?;
+    let Some(item) = table.get_mut(key) else {
+        return Ok(());
+    };
+
+    // `key = "1.0"` becomes `key = { version = "1.0" }`
+    if let Some(version) = item.as_value().filter(|e| e.is_str()) {
+        let mut table = toml_edit::InlineTable::new();
+        table.insert("version", version.clone());
+        *table.decor_mut() = version.decor().clone();
+        *item = toml_edit::Item::Value(table.into());
+    }
+
+    if let Some(entry) = item.as_table_like_mut() {
+        edit(entry);
+    }
+    // spacing of inline tables is held by their values
+    if let Some(entry) = item.as_inline_table_mut() {
+        entry.fmt();
+    }
+
+    Ok(())
+}
+
+/// Remove `features` from the `features` array of the dependency entry `dep`.
+///
+/// The array is removed once empty.
+pub(crate) fn remove_features(
+    manifest: &mut LocalManifest,
+    dep: &Dep,
+    features: &[String],
+) -> CargoResult<()> {
+    let table = manifest.get_table_mut(&dep.table)?;
+    let Some(item) = table.get_mut(&dep.key) else {
+        return Ok(());
+    };
+    let is_inline = item.is_inline_table();
+    let Some(entry) = item.as_table_like_mut() else {
+        return Ok(());
+    };
+    let Some(array) = entry.get_mut("features").and_then(|e| e.as_array_mut()) else {
+        return Ok(());
+    };
+
+    array.retain(|e| !e.as_str().is_some_and(|e| features.iter().any(|f| f == e)));
+    if array.is_empty() {
+        entry.remove("features");
+        // spacing before the closing brace was held by the removed value
+        if is_inline {
+            entry.fmt();
+        }
+    } else {
+        array.fmt();
+    }
+
+    Ok(())
+}
+
+/// Insert `[lints] workspace = true` in a manifest without `[lints]` table.
+pub(crate) fn inherit_workspace_lints(manifest: &mut LocalManifest) {
+    let mut lints = toml_edit::Table::new();
+    lints.insert("workspace", toml_edit::value(true));
+
+    manifest.data.insert("lints", toml_edit::Item::Table(lints));
+}
+
+/// Replace member `dependencies` inheriting `workspace_dep` by their merge with it.
+///
+/// Member-local keys (`features`, `optional`, `public`) are kept, and `default-features` is set to
+/// its effective value.
+pub(crate) fn inline_workspace_dependency(
+    workspace: &Workspace<'_>,
+    manifest: &mut LocalManifest,
+    workspace_dep: &Dep,
+    dependencies: &[Dep],
+) -> CargoResult<()> {
+    let crate_root = manifest
+        .path
+        .parent()
+        .expect("manifest path is absolute")
+        .to_owned();
+
+    for dep in dependencies {
+        let merged = merged_dependency(&workspace_dep.spec, &dep.spec);
+
+        let table = manifest.get_table_mut(&dep.table)?;
+        let Some((mut dep_key, dep_item)) = table
+            .as_table_like_mut()
+            .and_then(|table| table.get_key_value_mut(&dep.key))
+        else {
+            continue;
+        };
+
+        if let Some(value) = dep_item.as_value() {
+            let decor = value.decor().clone();
+            let mut item = merged.to_toml(
+                workspace.gctx(),
+                workspace.root(),
+                &crate_root,
+                &Features::default(),
+            )?;
+            if let Some(value) = item.as_value_mut() {
+                *value.decor_mut() = decor;
+            }
+            *dep_item = item;
+        } else {
+            merged.update_toml(
+                workspace.gctx(),
+                workspace.root(),
+                &crate_root,
+                &Features::default(),
+                &mut dep_key,
+                dep_item,
+            )?;
+            // keys of the workspace entry are appended, `taplo` keeps them sorted
+            if let Some(table) = dep_item.as_table_mut() {
+                table.sort_values();
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn merged_dependency(workspace_dep: &Dependency, dep: &Dependency) -> Dependency {
+    let mut merged = workspace_dep.clone();
+    merged.optional = dep.optional;
+    merged.public = dep.public;
+
+    // member `default-features` only matters when it turns default features back on
+    merged.default_features = match (workspace_dep.default_features(), dep.default_features()) {
+        (Some(false), Some(true)) | (Some(true) | None, _) => None,
+        (Some(false), _) => Some(false),
+    };
+
+    if let Some(features) = &dep.features {
+        merged
+            .features
+            .get_or_insert_with(Default::default)
+            .extend(features.iter().cloned());
+    }
+
+    merged
+}
+
+/// Entry `key` inheriting `dep`, keeping its member-local keys, ie `clap = { workspace = true }`.
+pub(crate) fn inheriting_entry(key: &str, dep: &Dependency) -> String {
+    let mut dep = dep.clone();
+    dep.default_features = None;
+
+    format!("{key} = {}", inherited_dependency(&dep))
+}
+
+/// Dependency entry `item` as an inline table, ie `"1.0"` as `{ version = "1.0" }`.
+pub(crate) fn inline_entry(item: &toml_edit::Item) -> Option<toml_edit::InlineTable> {
+    let mut table = match item {
+        toml_edit::Item::Value(toml_edit::Value::String(_)) => {
+            let mut table = toml_edit::InlineTable::new();
+            table.insert("version", item.as_value()?.clone());
+            table
+        }
+        toml_edit::Item::Value(toml_edit::Value::InlineTable(table)) => table.clone(),
+        toml_edit::Item::Table(table) => table.clone().into_inline_table(),
+        _ => return None,
+    };
+    table.decor_mut().clear();
+    table.fmt();
+
+    Some(table)
+}
+
+fn inherited_dependency(dep: &Dependency) -> toml_edit::InlineTable {
+    let mut table = toml_edit::InlineTable::new();
+    table.insert("workspace", true.into());
+
+    if let Some(default_features) = dep.default_features {
+        table.insert("default-features", default_features.into());
+    }
+    if let Some(features) = &dep.features {
+        table.insert("features", features.iter().collect());
+    }
+    if let Some(optional) = dep.optional {
+        table.insert("optional", optional.into());
+    }
+    if let Some(public) = dep.public {
+        table.insert("public", public.into());
+    }
+
+    table.fmt();
+    table
+}
+
+fn workspace_dependency(
+    workspace: &Workspace<'_>,
+    root_manifest: &LocalManifest,
+    key: &str,
+) -> CargoResult<Option<Dependency>> {
+    let Ok(table) = root_manifest.get_table(&workspace_dependencies_path()) else {
+        return Ok(None);
+    };
+
+    table
+        .get(key)
+        .map(|item| {
+            Dependency::from_toml(
+                workspace.gctx(),
+                workspace.root(),
+                workspace.root(),
+                &Features::default(),
+                key,
+                item,
+            )
+        })
+        .transpose()
+}
+
+fn add_workspace_dependency(
+    workspace: &Workspace<'_>,
+    root_manifest: &mut LocalManifest,
+    key: &str,
+    dep: &Dependency,
+    default_features: bool,
+) -> CargoResult<()> {
+    let mut ws_dep = Dependency::new(&dep.name);
+    ws_dep.source = dep.source.clone();
+    ws_dep.registry = dep.registry.clone();
+    if key != dep.name {
+        ws_dep = ws_dep.set_rename(key);
+    }
+    if !default_features {
+        ws_dep = ws_dep.set_default_features(false);
+    }
+
+    let table_path = workspace_dependencies_path();
+    let was_sorted = root_manifest
+        .get_table(&table_path)
+        .ok()
+        .and_then(|table| table.as_table_like())
+        .map(|table| table.iter().map(|(key, _)| key).is_sorted())
+        .unwrap_or(true);
+
+    root_manifest.insert_into_table(
+        &table_path,
+        &ws_dep,
+        workspace.gctx(),
+        workspace.root(),
+        &Features::default(),
+    )?;
+
+    if was_sorted
+        && let Some(table) = root_manifest
+            .get_table_mut(&table_path)?
+            .as_table_like_mut()
+    {
+        table.sort_values();
+    }
+
+    Ok(())
+}
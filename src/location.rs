@@ -0,0 +1,69 @@
+use serde::Serialize;
+use std::ops::Range;
+
+/// Position of a key in a manifest, 1-based.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub(crate) struct Location {
+    pub(crate) line: usize,
+    pub(crate) column: usize,
+}
+
+/// Source spans of the keys of a manifest.
+///
+/// The manifest is parsed again with `toml_edit::Document`, as the editable `DocumentMut` of
+/// `LocalManifest` does not keep spans.
+pub(crate) struct ManifestSpans {
+    document: Option<toml_edit::Document<String>>,
+}
+
+impl ManifestSpans {
+    pub(crate) fn new(contents: &str) -> Self {
+        Self {
+            document: toml_edit::Document::parse(contents.to_owned()).ok(),
+        }
+    }
+
+    fn key_value<S: AsRef<str>>(&self, path: &[S]) -> Option<(&toml_edit::Key, &toml_edit::Item)> {
+        let document = self.document.as_ref()?;
+        let (last, parents) = path.split_last()?;
+
+        let mut table = document.as_table() as &dyn toml_edit::TableLike;
+        for key in parents {
+            table = table.get(key.as_ref())?.as_table_like()?;
+        }
+
+        table.get_key_value(last.as_ref())
+    }
+
+    /// Span of the last key of `path`, ie `["workspace", "dependencies", "anyhow"]`.
+    pub(crate) fn span<S: AsRef<str>>(&self, path: &[S]) -> Option<Range<usize>> {
+        let (key, item) = self.key_value(path)?;
+        key.span().or_else(|| item.span())
+    }
+
+    /// Location of the last key of `path`, ie `["workspace", "dependencies", "anyhow"]`.
+    pub(crate) fn locate<S: AsRef<str>>(&self, path: &[S]) -> Option<Location> {
+        let span = self.span(path)?;
+        self.location(span.start)
+    }
+
+    /// Location of the string `value` in the array at `path`.
+    pub(crate) fn locate_value<S: AsRef<str>>(&self, path: &[S], value: &str) -> Option<Location> {
+        let (_, item) = self.key_value(path)?;
+        let value = item
+            .as_array()?
+            .iter()
+            .find(|e| e.as_str() == Some(value))?;
+        self.location(value.span()?.start)
+    }
+
+    fn location(&self, offset: usize) -> Option<Location> {
+        let contents = self.document.as_ref()?.raw().get(..offset)?;
+        let line_start = contents.rfind('\n').map(|e| e + 1).unwrap_or(0);
+
+        Some(Location {
+            line: contents.matches('\n').count() + 1,
+            column: contents[line_start..].chars().count() + 1,
+        })
+    }
+}
@@ -5,24 +5,31 @@
 
 use anyhow::anyhow;
 use cargo::CargoResult;
-use cargo::core::{Features, SourceId, Workspace};
+use cargo::core::Workspace;
 use cargo::util::context::GlobalContext;
-use cargo::util::interning::InternedString;
-use cargo::util::toml::read_manifest;
-use cargo::util::toml_mut::dependency::Source;
 use log::debug;
-use std::collections::{HashMap, HashSet};
+use std::env;
 use std::path::PathBuf;
-use std::{env, vec};
-use termtree::Tree;
+
+use crate::manifests::{Member, Root};
+use crate::output::{Format, PathStyle};
+use crate::report::Report;
+
+mod checks;
+mod config;
+mod fix;
+mod location;
+mod manifests;
+mod output;
+mod report;
 
 #[derive(argh::FromArgs)]
 #[argh(description = r#"
 cargo-neat: Remove unused workspace dependencies
 
 Exit code:
-    0:  when no unused dependencies are found
-    1:  when at least one unused dependency is found
+    0:  when no issue is found
+    1:  when at least one issue is found, warnings included
     2:  on error
 "#)]
 struct CliArgs {
@@ -30,10 +37,66 @@ struct CliArgs {
     #[argh(switch)]
     version: bool,
 
-    /// allow only workspace dependency (ie "workspace = true")
+    /// allow only workspace dependency (ie "workspace = true"), including for
+    /// members, and inherited lints when `[workspace.lints]` is declared
     #[argh(switch, short = 'm')]
     mandatory_workspace_dependencies: bool,
 
+    /// report member `default-features = false` ignored on workspace
+    /// dependencies enabling default features
+    #[argh(switch)]
+    ineffective_default_features: bool,
+
+    /// report member features already enabled by workspace dependencies
+    #[argh(switch)]
+    redundant_features: bool,
+
+    /// report crates required with different versions across the workspace
+    #[argh(switch)]
+    version_drift: bool,
+
+    /// report features enabled by every member using a workspace dependency
+    #[argh(switch)]
+    hoist_features: bool,
+
+    /// report registry crates declared by several members outside workspace
+    /// dependencies
+    #[argh(switch)]
+    promote: bool,
+
+    /// report workspace dependencies used by a single member, not allowed
+    /// with -m which requires workspace dependencies
+    #[argh(switch)]
+    demote: bool,
+
+    /// report unused `[workspace.package]` fields
+    #[argh(switch)]
+    workspace_package: bool,
+
+    /// allow only inherited package fields (ie "edition.workspace = true")
+    /// for the fields of `[workspace.package]`
+    #[argh(switch)]
+    mandatory_workspace_package: bool,
+
+    /// allow only git dependencies pinned with a `rev` or `tag`, reporting
+    /// the commit locked in `Cargo.lock` for the others
+    #[argh(switch)]
+    pinned_git: bool,
+
+    /// fix the reported issues when possible, ie remove unused workspace
+    /// dependencies or turn non workspace dependencies into workspace ones
+    #[argh(switch)]
+    fix: bool,
+
+    /// output format: text (default), diagnostic, json, sarif or github
+    #[argh(option, default = "Format::Text")]
+    format: Format,
+
+    /// style of the manifest paths: absolute (default, relative for sarif
+    /// and github), relative to the current directory or workspace
+    #[argh(option)]
+    path_style: Option<PathStyle>,
+
     /// path to directory that must be scanned.
     #[argh(positional, greedy)]
     path: Option<PathBuf>,
@@ -68,6 +131,13 @@ fn run() -> CargoResult<bool> {
         std::process::exit(0);
     }
 
+    // demoted dependencies would be reported by `-m`, and moved back on every fix
+    if args.demote && args.mandatory_workspace_dependencies {
+        return Err(anyhow!(
+            "`--demote` cannot be used with `-m`, which requires workspace dependencies"
+        ));
+    }
+
     let path = match args.path {
         Some(dir) => {
             debug!("Running from location {:?}", dir);
@@ -96,146 +166,245 @@ fn run() -> CargoResult<bool> {
     let workspace_member_names: Vec<_> = workspace_members.iter().map(|e| e.name()).collect();
     debug!("Workspace members : {:?}", workspace_member_names);
 
-    // read virtual manifest
-    let source_id = SourceId::for_manifest_path(root_cargo_toml)?;
-    let manifest = read_manifest(root_cargo_toml, source_id, &gctx)?;
-
-    match manifest {
-        cargo::core::EitherManifest::Real(_) => Err(anyhow!(
-            "Failed to read virtual manifest at `{}`. Maybe you don't use a cargo workspace?",
-            root_cargo_toml.display()
-        )),
-        cargo::core::EitherManifest::Virtual(virtual_manifest) => {
-            let workspace_dependencies = virtual_manifest
-                .document()
-                .get_ref()
-                .get("workspace")
-                .and_then(|e| e.get_ref().get("dependencies"))
-                .and_then(|e| e.get_ref().as_table())
-                .map(|e| {
-                    e.keys()
-                        .map(|e| e.clone().into_inner())
-                        .collect::<HashSet<_>>()
+    let mut root = Root::load(&workspace)?;
+    debug!(
+        "Workspace dependencies : {:?}",
+        root.dependencies.iter().map(|e| &e.key).collect::<Vec<_>>()
+    );
+    debug!("Workspace config : {:?}", root.config);
+
+    let mut members = workspace_members
+        .into_iter()
+        .map(|pkg| Member::load(&workspace, pkg))
+        .collect::<CargoResult<Vec<_>>>()?;
+
+    // only needed to know which git commits and overrides are locked
+    let resolve = if args.pinned_git || checks::patch::declares_overrides(&root) {
+        cargo::ops::load_pkg_lockfile(&workspace)?
+    } else {
+        None
+    };
+
+    let mut report = Report::new(root_cargo_toml);
+    report.add_source(&root.path, &root.manifest.raw);
+    for member in members.iter().filter(|e| !e.is_root) {
+        report.add_source(member.manifest_path(), &member.manifest.raw);
+    }
+    checks::unused::check(&root, &members, &mut report);
+    checks::patch::check(&root, resolve.as_ref(), &mut report);
+    if args.ineffective_default_features {
+        checks::default_features::check(&root, &members, &mut report);
+    }
+    if args.redundant_features {
+        checks::features::check(&root, &members, &mut report);
+    }
+    if args.mandatory_workspace_dependencies {
+        checks::mandatory::check(&root, &members, &mut report);
+        checks::internal::check(&root, &members, &mut report);
+        checks::lints::check(&root, &members, &mut report);
+    }
+    if args.version_drift {
+        checks::drift::check(&root, &members, &mut report);
+    }
+    if args.hoist_features {
+        checks::hoist::check(&root, &members, &mut report);
+    }
+    if args.promote {
+        checks::promote::check(&root, &members, &mut report);
+    }
+    if args.demote {
+        checks::demote::check(&root, &members, &mut report);
+    }
+    if args.workspace_package {
+        checks::package::check_unused(&root, &members, &mut report);
+    }
+    if args.mandatory_workspace_package {
+        checks::package::check_mandatory(&root, &members, &mut report);
+    }
+    if args.pinned_git {
+        checks::git::check(&root, &members, resolve.as_ref(), &mut report);
+    }
+
+    if args.fix {
+        let hoisted_features: Vec<_> = if args.hoist_features {
+            checks::hoist::hoistable_features(&root, &members)
+                .into_iter()
+                .map(|(dep, features)| {
+                    let features: Vec<_> = features.into_iter().map(str::to_owned).collect();
+                    (dep.key.clone(), features)
                 })
-                .unwrap_or_default();
-
-            debug!("Workspace dependencies : {:?}", workspace_dependencies);
-
-            let mut unused_workspace_dependencies = workspace_dependencies;
-            let mut mandatory_workspace_dependencies_issues: HashMap<InternedString, Vec<String>> =
-                HashMap::new();
-
-            for pkg in workspace_members {
-                let local_manifest =
-                    cargo::util::toml_mut::manifest::LocalManifest::try_new(pkg.manifest_path())?;
-
-                if args.mandatory_workspace_dependencies {
-                    let deps_other: Vec<_> = local_manifest
-                        .get_dependencies(&workspace, &Features::default())
-                        .flat_map(|dep| dep.2.map(|e| (dep.0, e.source)))
-                        .filter_map(|dep| dep.1.map(|e| (dep.0, e)))
-                        .collect();
-
-                    for (dep, source) in deps_other {
-                        if let Source::Registry(_) = source {
-                            let values = mandatory_workspace_dependencies_issues
-                                .entry(pkg.name())
-                                .or_insert(vec![]);
-                            values.push(dep);
-                        }
-                    }
+                .collect()
+        } else {
+            vec![]
+        };
+        let demoted_dependencies: Vec<_> = if args.demote {
+            checks::demote::single_use_dependencies(&root, &members)
+                .into_iter()
+                .map(|(dep, member)| (dep.clone(), member.manifest_path().to_path_buf()))
+                .collect()
+        } else {
+            vec![]
+        };
+        // dependencies turned into workspace ones, with the manifest of their member
+        let (migrated_dependencies, unversioned_dependencies) = if args
+            .mandatory_workspace_dependencies
+        {
+            let mut migrated = vec![];
+            for member in &members {
+                let manifest_path = member.manifest_path().to_path_buf();
+                for (dep, _) in
+                    checks::mandatory::non_workspace_dependencies(&root, member, &members)
+                {
+                    migrated.push((manifest_path.clone(), checks::mandatory::resolved(dep)));
                 }
-
-                for dep in pkg.dependencies() {
-                    let name = dep.package_name();
-                    let name: &str = name.as_ref();
-                    unused_workspace_dependencies.remove(name);
+                for (dep, target) in checks::internal::internal_path_dependencies(member, &members)
+                {
+                    let dep = checks::internal::versioned(dep, target);
+                    migrated.push((manifest_path.clone(), dep));
                 }
             }
 
-            if unused_workspace_dependencies.is_empty()
-                && mandatory_workspace_dependencies_issues.is_empty()
-            {
-                println!("No unused workspace dependencies");
-
-                if args.mandatory_workspace_dependencies {
-                    println!("No non workspace dependencies");
-                }
+            let unversioned: Vec<_> =
+                checks::internal::unversioned_workspace_dependencies(&root, &members)
+                    .into_iter()
+                    .map(|(dep, target)| (dep.key.clone(), target.package.version().to_string()))
+                    .collect();
+            (migrated, unversioned)
+        } else {
+            (vec![], vec![])
+        };
+        // entries added for migrated dependencies disable default features as soon as one member does
+        let no_default_features: Vec<_> = migrated_dependencies
+            .iter()
+            .filter(|(_, dep)| dep.spec.default_features() == Some(false))
+            .map(|(_, dep)| dep.key.clone())
+            .collect();
+        let inherit_lints =
+            args.mandatory_workspace_dependencies && checks::lints::has_workspace_lints(&root);
+
+        // versions are set first, for migrated members to match them
+        for (key, version) in &unversioned_dependencies {
+            fix::set_workspace_version(&mut root.manifest, key, version)?;
+        }
 
-                Ok(false)
+        for member in &mut members {
+            let mut changed = false;
+
+            let redundant_features: Vec<_> = if args.redundant_features {
+                checks::features::redundant_features(&root, member)
+                    .into_iter()
+                    .map(|(dep, features)| {
+                        let features: Vec<_> = features.into_iter().map(str::to_owned).collect();
+                        (dep.clone(), features)
+                    })
+                    .collect()
             } else {
-                if !unused_workspace_dependencies.is_empty() {
-                    let mut unused_workspace_dependencies: Vec<_> = unused_workspace_dependencies
-                        .into_iter()
-                        .map(|e| e.to_string())
-                        .collect();
-                    unused_workspace_dependencies.sort();
+                vec![]
+            };
+            for (dep, features) in &redundant_features {
+                fix::remove_features(member.manifest_mut(&mut root), dep, features)?;
+                changed = true;
+            }
 
-                    eprintln!(
-                        "{}",
-                        tree(
-                            InternedString::new("Unused workspace dependencies :"),
-                            &[(
-                                InternedString::new(
-                                    root_cargo_toml
-                                        .to_str()
-                                        .ok_or(anyhow!("cannot get root workspace"))?
-                                ),
-                                unused_workspace_dependencies
-                            )]
-                        )?
-                    );
-                }
+            let hoisted_dependencies: Vec<_> = hoisted_features
+                .iter()
+                .flat_map(|(key, features)| {
+                    checks::hoist::inheriting(member, key).map(move |dep| (dep.clone(), features))
+                })
+                .collect();
+            for (dep, features) in hoisted_dependencies {
+                fix::remove_features(member.manifest_mut(&mut root), &dep, features)?;
+                changed = true;
+            }
 
-                if !mandatory_workspace_dependencies_issues.is_empty() {
-                    let parent_folder = root_cargo_toml
-                        .parent()
-                        .ok_or(anyhow!("cannot get root workspace folder"))?;
-
-                    let mut mandatory_workspace_dependencies_issues: Vec<_> =
-                        mandatory_workspace_dependencies_issues
-                            .into_iter()
-                            .flat_map(|e| {
-                                PathBuf::from(parent_folder)
-                                    .join(e.0)
-                                    .join("Cargo.toml")
-                                    .to_str()
-                                    .ok_or(anyhow!("cannot get root workspace folder"))
-                                    .map(|res| (InternedString::new(res), e.1))
-                            })
-                            .collect();
-                    mandatory_workspace_dependencies_issues.sort();
+            let manifest_path = member.manifest_path().to_path_buf();
+            for (workspace_dep, _) in demoted_dependencies
+                .iter()
+                .filter(|(_, e)| *e == manifest_path)
+            {
+                let dependencies: Vec<_> = checks::hoist::inheriting(member, &workspace_dep.key)
+                    .cloned()
+                    .collect();
+                fix::inline_workspace_dependency(
+                    &workspace,
+                    member.manifest_mut(&mut root),
+                    workspace_dep,
+                    &dependencies,
+                )?;
+                changed = true;
+            }
 
+            let non_workspace_dependencies: Vec<_> = migrated_dependencies
+                .iter()
+                .filter(|(e, _)| *e == manifest_path)
+                .map(|(_, dep)| dep.clone())
+                .collect();
+            if !non_workspace_dependencies.is_empty() {
+                // the root package is edited through the root manifest, written last
+                let member_manifest = (!member.is_root).then_some(&mut member.manifest);
+                let skipped = fix::inherit_workspace_dependencies(
+                    &workspace,
+                    &mut root.manifest,
+                    member_manifest,
+                    &non_workspace_dependencies,
+                    &no_default_features,
+                )?;
+                for (key, reason) in skipped {
                     eprintln!(
-                        "{}",
-                        tree(
-                            InternedString::new("Non workspace dependencies :"),
-                            &mandatory_workspace_dependencies_issues
-                        )?
+                        "Not moved to workspace dependencies: `{key}` of {}, which {reason}",
+                        manifest_path.display()
                     );
                 }
+                changed = true;
+            }
 
-                Ok(true)
+            // members with their own lints are left as is, as inheriting would drop them
+            if inherit_lints && checks::lints::misses_lints(member) {
+                fix::inherit_workspace_lints(member.manifest_mut(&mut root));
+                changed = true;
+            }
+
+            if changed && !member.is_root {
+                member.manifest.write()?;
             }
         }
-    }
-}
 
-fn tree(
-    root: InternedString,
-    issues: &[(InternedString, Vec<String>)],
-) -> anyhow::Result<Tree<InternedString>> {
-    let mut tree: Tree<InternedString> = Tree::new(root);
+        for (key, features) in &hoisted_features {
+            fix::add_workspace_features(&mut root.manifest, key, features)?;
+        }
+        if args.ineffective_default_features {
+            for key in checks::default_features::movable_to_workspace(&root, &members) {
+                fix::disable_workspace_default_features(&mut root.manifest, key)?;
+            }
+        }
 
-    for (pkg, deps) in issues {
-        let mut pkg: Tree<InternedString> = Tree::new(InternedString::new(pkg));
+        let demoted_dependencies: Vec<_> = demoted_dependencies
+            .into_iter()
+            .map(|(dep, _)| dep.key)
+            .collect();
+        fix::remove_workspace_dependencies(&mut root.manifest, &demoted_dependencies)?;
+    }
 
-        for dep in deps {
-            pkg.push(InternedString::new(dep.as_ref()));
+    if args.fix && report.has_findings() {
+        let unused_workspace_dependencies: Vec<_> =
+            checks::unused::unused_workspace_dependencies(&root, &members)
+                .into_iter()
+                .map(|e| e.key.clone())
+                .collect();
+        fix::remove_workspace_dependencies(&mut root.manifest, &unused_workspace_dependencies)?;
+        if let Some(resolve) = &resolve {
+            let unused_overrides = [
+                checks::patch::unused_patches(&root, resolve),
+                checks::patch::unused_replacements(&root, resolve),
+            ]
+            .concat();
+            fix::remove_overrides(&mut root.manifest, &unused_overrides)?;
         }
 
-        tree.push(pkg);
+        root.manifest.write()?;
     }
 
-    Ok(tree)
+    output::print(&report, args.format, args.path_style)?;
+
+    Ok(report.has_findings())
 }
@@ -0,0 +1,188 @@
+use crate::config::{MemberConfig, WorkspaceConfig};
+use crate::location::{Location, ManifestSpans};
+use anyhow::anyhow;
+use cargo::CargoResult;
+use cargo::core::{Features, Package, Workspace};
+use cargo::util::toml_mut::dependency::Dependency;
+use cargo::util::toml_mut::manifest::LocalManifest;
+use log::debug;
+use std::path::{Path, PathBuf};
+
+/// Path of `[workspace.dependencies]` in the root manifest.
+pub(crate) const WORKSPACE_DEPENDENCIES: [&str; 2] = ["workspace", "dependencies"];
+
+/// Path of `[workspace.package]` in the root manifest.
+pub(crate) const WORKSPACE_PACKAGE: [&str; 2] = ["workspace", "package"];
+
+/// A dependency entry of a manifest.
+#[derive(Clone, Debug)]
+pub(crate) struct Dep {
+    /// key of the entry, which differs from the package name when renamed
+    pub(crate) key: String,
+    /// path of the table holding the entry, ie `["target", "cfg(unix)", "dependencies"]`
+    pub(crate) table: Vec<String>,
+    pub(crate) spec: Dependency,
+}
+
+impl Dep {
+    /// Path of the entry in the manifest.
+    pub(crate) fn key_path(&self) -> Vec<&str> {
+        self.table
+            .iter()
+            .map(String::as_str)
+            .chain([self.key.as_str()])
+            .collect()
+    }
+
+    pub(crate) fn package(&self) -> &str {
+        &self.spec.name
+    }
+}
+
+/// The root manifest of the workspace.
+pub(crate) struct Root {
+    pub(crate) path: PathBuf,
+    pub(crate) manifest: LocalManifest,
+    pub(crate) spans: ManifestSpans,
+    pub(crate) config: WorkspaceConfig,
+    /// entries of `[workspace.dependencies]`
+    pub(crate) dependencies: Vec<Dep>,
+}
+
+impl Root {
+    pub(crate) fn load(workspace: &Workspace<'_>) -> CargoResult<Self> {
+        let path = workspace.root_manifest().to_path_buf();
+        let manifest = LocalManifest::try_new(&path)?;
+
+        if manifest.data.get("workspace").is_none() {
+            return Err(anyhow!(
+                "Failed to read workspace manifest at `{}`. Maybe you don't use a cargo workspace?",
+                path.display()
+            ));
+        }
+
+        let table_path = WORKSPACE_DEPENDENCIES.map(str::to_owned);
+        let dependencies = manifest
+            .get_table(&table_path)
+            .ok()
+            .and_then(|e| e.as_table_like())
+            .into_iter()
+            .flat_map(|e| e.iter())
+            .filter_map(|(key, item)| {
+                let spec = Dependency::from_toml(
+                    workspace.gctx(),
+                    workspace.root(),
+                    workspace.root(),
+                    &Features::default(),
+                    key,
+                    item,
+                )
+                .inspect_err(|err| debug!("Skipping workspace dependency `{key}` : {err}"))
+                .ok()?;
+
+                Some(Dep {
+                    key: key.to_owned(),
+                    table: table_path.to_vec(),
+                    spec,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            spans: ManifestSpans::new(&manifest.raw),
+            config: WorkspaceConfig::read(&manifest)?,
+            path,
+            manifest,
+            dependencies,
+        })
+    }
+
+    pub(crate) fn dependency(&self, key: &str) -> Option<&Dep> {
+        self.dependencies.iter().find(|e| e.key == key)
+    }
+
+    pub(crate) fn locate(&self, dep: &Dep) -> Option<Location> {
+        self.spans.locate(&dep.key_path())
+    }
+
+    /// Keys of `[workspace.package]`, which members can inherit.
+    pub(crate) fn package_fields(&self) -> Vec<&str> {
+        self.manifest
+            .get_table(&WORKSPACE_PACKAGE.map(str::to_owned))
+            .ok()
+            .and_then(|e| e.as_table_like())
+            .map(|e| e.iter().map(|(key, _)| key).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// A member of the workspace.
+pub(crate) struct Member {
+    pub(crate) package: Package,
+    /// whether the member is the root package, whose manifest is the root manifest
+    pub(crate) is_root: bool,
+    pub(crate) manifest: LocalManifest,
+    pub(crate) spans: ManifestSpans,
+    pub(crate) config: MemberConfig,
+    /// entries of all the dependency tables
+    pub(crate) dependencies: Vec<Dep>,
+}
+
+impl Member {
+    pub(crate) fn load(workspace: &Workspace<'_>, package: &Package) -> CargoResult<Self> {
+        let manifest = LocalManifest::try_new(package.manifest_path())?;
+        let dependencies = manifest
+            .get_dependencies(workspace, &Features::default())
+            .filter_map(|(key, table, spec)| {
+                let spec = spec
+                    .inspect_err(|err| debug!("Skipping dependency `{key}` : {err}"))
+                    .ok()?;
+
+                Some(Dep {
+                    key,
+                    table: table.to_table().into_iter().map(str::to_owned).collect(),
+                    spec,
+                })
+            })
+            .collect();
+
+        Ok(Self {
+            package: package.clone(),
+            is_root: package.manifest_path() == workspace.root_manifest(),
+            spans: ManifestSpans::new(&manifest.raw),
+            config: MemberConfig::read(&manifest)?,
+            manifest,
+            dependencies,
+        })
+    }
+
+    pub(crate) fn manifest_path(&self) -> &Path {
+        self.package.manifest_path()
+    }
+
+    pub(crate) fn locate(&self, dep: &Dep) -> Option<Location> {
+        self.spans.locate(&dep.key_path())
+    }
+
+    /// Editable manifest of the member, which is the root one for the root package.
+    pub(crate) fn manifest_mut<'a>(&'a mut self, root: &'a mut Root) -> &'a mut LocalManifest {
+        if self.is_root {
+            &mut root.manifest
+        } else {
+            &mut self.manifest
+        }
+    }
+
+    /// Value of `key` in the `[package]` table of the member.
+    pub(crate) fn package_field(&self, key: &str) -> Option<&toml_edit::Item> {
+        self.manifest.data.get("package")?.get(key)
+    }
+
+    /// Whether `key` of `[package]` is inherited with `key.workspace = true`.
+    pub(crate) fn inherits_package_field(&self, key: &str) -> bool {
+        self.package_field(key)
+            .and_then(|e| e.get("workspace"))
+            .and_then(|e| e.as_bool())
+            .unwrap_or(false)
+    }
+}
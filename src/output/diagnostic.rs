@@ -0,0 +1,72 @@
+use crate::output::text;
+use crate::report::{Finding, Report};
+
+/// Print a rustc-style diagnostic per finding on stderr, quoting the offending manifest line.
+///
+/// Findings follow the sections of the text output, which prints the success messages when
+/// nothing is found.
+pub(crate) fn print(report: &Report) -> anyhow::Result<()> {
+    if !report.has_findings() {
+        return text::print(report);
+    }
+
+    for rule in &report.rules {
+        for finding in report.findings(*rule) {
+            eprintln!("{}", diagnostic(report, finding));
+        }
+    }
+
+    Ok(())
+}
+
+fn diagnostic(report: &Report, finding: &Finding) -> String {
+    let mut lines = vec![format!(
+        "{}[{}]: {}",
+        finding.rule.level().as_str(),
+        finding.rule.id(),
+        finding.message()
+    )];
+
+    let source = finding.location.and_then(|location| {
+        let line = report
+            .sources
+            .get(&finding.manifest)?
+            .lines()
+            .nth(location.line - 1)?;
+        Some((location, line))
+    });
+    let gutter = source.map_or(0, |(location, _)| location.line.to_string().len());
+    let margin = " ".repeat(gutter);
+
+    lines.push(format!("{margin}--> {}", finding.position()));
+    if let Some((location, line)) = source {
+        let indent = " ".repeat(location.column - 1);
+        let carets = "^".repeat(token_width(line, location.column));
+        lines.push(format!("{margin} |"));
+        lines.push(format!("{} | {line}", location.line));
+        lines.push(format!("{margin} | {indent}{carets}"));
+        lines.push(format!("{margin} |"));
+    }
+
+    lines.push(format!("{margin} = help: {}", finding.rule.help()));
+    if let Some(suggestion) = &finding.suggestion {
+        lines.push(format!("{margin} = suggestion: `{suggestion}`"));
+    }
+
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Width of the key or value starting at the 1-based `column` of `line`, quotes included.
+fn token_width(line: &str, column: usize) -> usize {
+    let mut chars = line.chars().skip(column - 1);
+    match chars.next() {
+        Some(quote @ ('"' | '\'')) => chars.position(|e| e == quote).map_or(1, |e| e + 2),
+        Some(_) => {
+            1 + chars
+                .take_while(|e| !e.is_whitespace() && !matches!(e, '=' | ',' | ']' | '.'))
+                .count()
+        }
+        None => 1,
+    }
+}
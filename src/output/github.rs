@@ -0,0 +1,46 @@
+use crate::report::{Finding, Report};
+
+/// Print a GitHub Actions workflow command per finding on stdout, shown as annotations of the
+/// pull request, with the level of its rule.
+pub(crate) fn print(report: &Report) -> anyhow::Result<()> {
+    for finding in report.sorted_findings() {
+        println!("{}", command(finding));
+    }
+
+    Ok(())
+}
+
+fn command(finding: &Finding) -> String {
+    let mut properties = vec![format!(
+        "file={}",
+        escape_property(&finding.manifest.to_string_lossy().replace('\\', "/"))
+    )];
+    if let Some(location) = finding.location {
+        properties.push(format!("line={}", location.line));
+        properties.push(format!("col={}", location.column));
+    }
+    properties.push(format!(
+        "title={}",
+        escape_property(&format!("{} ({})", finding.rule.title(), finding.rule.id()))
+    ));
+
+    format!(
+        "::{} {}::{}",
+        finding.rule.level().as_str(),
+        properties.join(","),
+        escape_data(&finding.message())
+    )
+}
+
+/// Escape the message of a workflow command.
+fn escape_data(value: &str) -> String {
+    value
+        .replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escape a property of a workflow command, which also cannot hold `:` and `,`.
+fn escape_property(value: &str) -> String {
+    escape_data(value).replace(':', "%3A").replace(',', "%2C")
+}
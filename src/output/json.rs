@@ -0,0 +1,30 @@
+use crate::report::{Finding, Report, Rule};
+use serde::Serialize;
+use std::path::Path;
+
+/// Version of the JSON document, bumped on every breaking change of its layout.
+pub(crate) const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Serialize)]
+struct JsonReport<'a> {
+    version: u32,
+    exit_code: i32,
+    root_manifest: &'a Path,
+    rules: &'a [Rule],
+    findings: Vec<&'a Finding>,
+}
+
+/// Print the report as a single JSON document on stdout.
+pub(crate) fn print(report: &Report) -> anyhow::Result<()> {
+    let document = JsonReport {
+        version: SCHEMA_VERSION,
+        exit_code: report.exit_code(),
+        root_manifest: &report.root_manifest,
+        rules: &report.rules,
+        findings: report.sorted_findings(),
+    };
+
+    println!("{}", serde_json::to_string_pretty(&document)?);
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use crate::report::Report;
+use std::str::FromStr;
+
+pub(crate) use paths::PathStyle;
+
+mod diagnostic;
+mod github;
+mod json;
+mod paths;
+mod sarif;
+mod text;
+
+/// Output format of the report.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) enum Format {
+    /// human readable trees
+    #[default]
+    Text,
+    /// rustc-style diagnostics quoting the manifests
+    Diagnostic,
+    /// versioned JSON document, see [`json::SCHEMA_VERSION`]
+    Json,
+    /// SARIF 2.1.0 log, for code scanning
+    Sarif,
+    /// GitHub Actions workflow commands, shown as annotations
+    Github,
+}
+
+impl Format {
+    const VARIANTS: [(&'static str, Format); 5] = [
+        ("text", Format::Text),
+        ("diagnostic", Format::Diagnostic),
+        ("json", Format::Json),
+        ("sarif", Format::Sarif),
+        ("github", Format::Github),
+    ];
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, format)| *format)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::VARIANTS.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown format `{s}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Print the report in the requested format.
+///
+/// Manifest paths are absolute by default, except for SARIF and GitHub whose manifests are
+/// relative to the current directory, ie the root of the repository.
+pub(crate) fn print(
+    report: &Report,
+    format: Format,
+    path_style: Option<PathStyle>,
+) -> anyhow::Result<()> {
+    let path_style = path_style.unwrap_or(match format {
+        Format::Sarif | Format::Github => PathStyle::Relative,
+        _ => PathStyle::Absolute,
+    });
+    let base = paths::base(path_style, report)?;
+
+    match format {
+        Format::Text => text::print(&paths::relocate(report, base.as_deref())),
+        Format::Diagnostic => diagnostic::print(&paths::relocate(report, base.as_deref())),
+        Format::Json => json::print(&paths::relocate(report, base.as_deref())),
+        Format::Sarif => sarif::print(report, base.as_deref()),
+        Format::Github => github::print(&paths::relocate(report, base.as_deref())),
+    }
+}
@@ -0,0 +1,95 @@
+use crate::report::Report;
+use std::env;
+use std::path::{Component, Path, PathBuf};
+use std::str::FromStr;
+
+/// How manifest paths are printed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum PathStyle {
+    Absolute,
+    /// relative to the current directory
+    Relative,
+    /// relative to the root of the workspace
+    Workspace,
+}
+
+impl PathStyle {
+    const VARIANTS: [(&'static str, PathStyle); 3] = [
+        ("absolute", PathStyle::Absolute),
+        ("relative", PathStyle::Relative),
+        ("workspace", PathStyle::Workspace),
+    ];
+}
+
+impl FromStr for PathStyle {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::VARIANTS
+            .iter()
+            .find(|(name, _)| *name == s)
+            .map(|(_, style)| *style)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::VARIANTS.iter().map(|(name, _)| *name).collect();
+                format!(
+                    "unknown path style `{s}`, expected one of: {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Directory the manifest paths are printed relative to, `None` for absolute paths.
+pub(crate) fn base(style: PathStyle, report: &Report) -> anyhow::Result<Option<PathBuf>> {
+    Ok(match style {
+        PathStyle::Absolute => None,
+        PathStyle::Relative => Some(env::current_dir()?),
+        PathStyle::Workspace => report.root_manifest.parent().map(Path::to_path_buf),
+    })
+}
+
+/// Copy of `report` with its manifest paths relative to `base`.
+pub(crate) fn relocate(report: &Report, base: Option<&Path>) -> Report {
+    let Some(base) = base else {
+        return report.clone();
+    };
+
+    let mut report = report.clone();
+    report.root_manifest = relative_to(&report.root_manifest, base);
+    for finding in &mut report.findings {
+        finding.manifest = relative_to(&finding.manifest, base);
+    }
+    report.sources = report
+        .sources
+        .into_iter()
+        .map(|(manifest, contents)| (relative_to(&manifest, base), contents))
+        .collect();
+
+    report
+}
+
+/// `path` relative to `base`, going up with `..` when `path` is not under it.
+fn relative_to(path: &Path, base: &Path) -> PathBuf {
+    let mut path_components = path.components().peekable();
+    let mut base_components = base.components().peekable();
+    while let (Some(a), Some(b)) = (path_components.peek(), base_components.peek()) {
+        if a != b {
+            break;
+        }
+        path_components.next();
+        base_components.next();
+    }
+
+    // nothing in common, ie another drive on Windows
+    if base_components
+        .peek()
+        .is_some_and(|e| matches!(e, Component::Prefix(_)))
+    {
+        return path.to_path_buf();
+    }
+
+    base_components
+        .map(|_| Component::ParentDir)
+        .chain(path_components)
+        .collect()
+}
@@ -0,0 +1,103 @@
+use crate::report::{Finding, Report};
+use serde_json::{Value, json};
+use std::path::Path;
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SRCROOT: &str = "%SRCROOT%";
+
+/// Print the report as a SARIF 2.1.0 log on stdout.
+///
+/// Manifests under `base`, the current directory by default, are reported relative to
+/// `%SRCROOT%`, so that code scanning can match them with the files of the repository.
+pub(crate) fn print(report: &Report, base: Option<&Path>) -> anyhow::Result<()> {
+    let rules: Vec<_> = report
+        .rules
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id(),
+                "shortDescription": { "text": rule.title() },
+                "defaultConfiguration": { "level": rule.level().as_str() },
+            })
+        })
+        .collect();
+
+    let results: Vec<_> = report
+        .sorted_findings()
+        .into_iter()
+        .map(|finding| result(report, finding, base))
+        .collect();
+
+    let mut log = json!({
+        "$schema": SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "results": results,
+        }]
+    });
+    if let Some(base) = base {
+        log["runs"][0]["originalUriBaseIds"] = json!({
+            SRCROOT: { "uri": format!("{}/", file_uri(base)) }
+        });
+    }
+
+    println!("{}", serde_json::to_string_pretty(&log)?);
+
+    Ok(())
+}
+
+fn result(report: &Report, finding: &Finding, base: Option<&Path>) -> Value {
+    let relative = base.and_then(|e| finding.manifest.strip_prefix(e).ok());
+    let artifact_location = match relative {
+        Some(relative) => json!({ "uri": uri_path(relative), "uriBaseId": SRCROOT }),
+        None => json!({ "uri": file_uri(&finding.manifest) }),
+    };
+
+    let mut physical_location = json!({ "artifactLocation": artifact_location });
+    if let Some(location) = finding.location {
+        physical_location["region"] = json!({
+            "startLine": location.line,
+            "startColumn": location.column,
+        });
+    }
+
+    json!({
+        "ruleId": finding.rule.id(),
+        "ruleIndex": report.rules.iter().position(|rule| *rule == finding.rule),
+        "level": finding.rule.level().as_str(),
+        "message": { "text": finding.message() },
+        "locations": [{ "physicalLocation": physical_location }],
+    })
+}
+
+/// Percent-encoded path with `/` separators.
+fn uri_path(path: &Path) -> String {
+    path.to_string_lossy()
+        .replace('\\', "/")
+        .bytes()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || b"-._~/:".contains(&b) {
+                char::from(b).to_string()
+            } else {
+                format!("%{b:02X}")
+            }
+        })
+        .collect()
+}
+
+fn file_uri(path: &Path) -> String {
+    let path = uri_path(path);
+    if path.starts_with('/') {
+        format!("file://{path}")
+    } else {
+        format!("file:///{path}")
+    }
+}
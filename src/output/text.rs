@@ -0,0 +1,94 @@
+use crate::report::{Finding, Report};
+use anyhow::anyhow;
+use cargo::util::interning::InternedString;
+use std::collections::BTreeMap;
+use std::path::Path;
+use termtree::Tree;
+
+/// Print a tree per rule on stderr, or a success message per rule on stdout when nothing is found.
+pub(crate) fn print(report: &Report) -> anyhow::Result<()> {
+    if !report.has_findings() {
+        for rule in &report.rules {
+            println!("{}", rule.success_message());
+        }
+
+        return Ok(());
+    }
+
+    for rule in &report.rules {
+        let findings: Vec<_> = report.findings(*rule).collect();
+        if findings.is_empty() {
+            continue;
+        }
+
+        let nodes = if rule.by_package() {
+            let mut packages: BTreeMap<&str, Vec<&Finding>> = BTreeMap::new();
+            for finding in findings {
+                packages
+                    .entry(finding.package_name())
+                    .or_default()
+                    .push(finding);
+            }
+
+            packages
+                .into_iter()
+                .map(|(package, findings)| {
+                    // the group shows the suggestion shared by its findings, if any
+                    let label = findings
+                        .iter()
+                        .find_map(|e| e.suggestion.as_deref())
+                        .unwrap_or(package);
+                    Ok(tree(InternedString::new(label), manifests(&findings)?))
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        } else {
+            manifests(&findings)?
+        };
+
+        eprintln!(
+            "{}",
+            tree(InternedString::new(&format!("{} :", rule.title())), nodes)
+        );
+    }
+
+    Ok(())
+}
+
+/// A node per manifest, holding the labels of its findings with their `path:line:col`.
+fn manifests(findings: &[&Finding]) -> anyhow::Result<Vec<Tree<InternedString>>> {
+    let mut issues: BTreeMap<&Path, Vec<String>> = BTreeMap::new();
+    for finding in findings {
+        issues
+            .entry(finding.manifest.as_path())
+            .or_default()
+            .push(match finding.location {
+                Some(_) => format!("{} at {}", finding.label(), finding.position()),
+                None => finding.label(),
+            });
+    }
+
+    issues
+        .into_iter()
+        .map(|(manifest, names)| {
+            let manifest = manifest
+                .to_str()
+                .ok_or(anyhow!("cannot get manifest path"))?;
+            let leaves = names.iter().map(|e| Tree::new(InternedString::new(e)));
+
+            Ok(tree(InternedString::new(manifest), leaves))
+        })
+        .collect()
+}
+
+fn tree(
+    root: InternedString,
+    nodes: impl IntoIterator<Item = Tree<InternedString>>,
+) -> Tree<InternedString> {
+    let mut tree: Tree<InternedString> = Tree::new(root);
+
+    for node in nodes {
+        tree.push(node);
+    }
+
+    tree
+}
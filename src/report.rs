@@ -0,0 +1,453 @@
+use crate::location::Location;
+use serde::{Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Severity of the findings of a rule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Level {
+    Error,
+    Warning,
+}
+
+impl Level {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            Level::Error => "error",
+            Level::Warning => "warning",
+        }
+    }
+}
+
+/// A check performed on the workspace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) enum Rule {
+    /// `[workspace.dependencies]` entry used by no member
+    UnusedWorkspaceDependency,
+    /// `[workspace.metadata.cargo-neat]` ignored entry missing from `[workspace.dependencies]`
+    StaleIgnore,
+    /// `[patch.<registry>]` entry listed as unused in `Cargo.lock`
+    UnusedPatch,
+    /// `[replace]` entry matching no package of `Cargo.lock`
+    UnusedReplace,
+    /// member registry dependency not using `workspace = true`
+    NonWorkspaceDependency,
+    /// member alternate registry dependency not using `workspace = true`
+    NonWorkspaceAlternateRegistryDependency,
+    /// member git dependency not using `workspace = true`
+    NonWorkspaceGitDependency,
+    /// member path dependency, outside of the members, not using `workspace = true`
+    NonWorkspacePathDependency,
+    /// crate required with different versions across the workspace
+    VersionDrift,
+    /// `[workspace.package]` field inherited by no member
+    UnusedWorkspacePackageField,
+    /// member `[package]` field not using `workspace = true`
+    NonInheritedPackageField,
+    /// member not using `[lints] workspace = true`
+    NonInheritedLints,
+    /// member `default-features = false` ignored as the workspace entry enables default features
+    IneffectiveDefaultFeatures,
+    /// member feature already enabled by the `[workspace.dependencies]` entry
+    RedundantFeature,
+    /// feature enabled by every member using a `[workspace.dependencies]` entry
+    HoistableFeature,
+    /// registry crate declared by several members outside `[workspace.dependencies]`
+    SharedDependency,
+    /// `[workspace.dependencies]` entry inherited by a single member
+    SingleUseWorkspaceDependency,
+    /// member dependency on another member by `path` instead of `workspace = true`
+    InternalPathDependency,
+    /// `[workspace.dependencies]` entry of a member without `version`
+    UnversionedInternalDependency,
+    /// git dependency following a branch instead of a `rev` or `tag`
+    UnpinnedGitDependency,
+}
+
+impl Rule {
+    /// Stable identifier of the rule, used in machine-readable outputs.
+    pub(crate) fn id(self) -> &'static str {
+        match self {
+            Rule::UnusedWorkspaceDependency => "unused-workspace-dependency",
+            Rule::StaleIgnore => "stale-ignored-dependency",
+            Rule::UnusedPatch => "unused-patch",
+            Rule::UnusedReplace => "unused-replace",
+            Rule::NonWorkspaceDependency => "non-workspace-dependency",
+            Rule::NonWorkspaceAlternateRegistryDependency => {
+                "non-workspace-alternate-registry-dependency"
+            }
+            Rule::NonWorkspaceGitDependency => "non-workspace-git-dependency",
+            Rule::NonWorkspacePathDependency => "non-workspace-path-dependency",
+            Rule::VersionDrift => "version-drift",
+            Rule::UnusedWorkspacePackageField => "unused-workspace-package-field",
+            Rule::NonInheritedPackageField => "non-inherited-package-field",
+            Rule::NonInheritedLints => "non-inherited-lints",
+            Rule::IneffectiveDefaultFeatures => "ineffective-default-features",
+            Rule::RedundantFeature => "redundant-feature",
+            Rule::HoistableFeature => "hoistable-feature",
+            Rule::SharedDependency => "shared-dependency",
+            Rule::SingleUseWorkspaceDependency => "single-use-workspace-dependency",
+            Rule::InternalPathDependency => "internal-path-dependency",
+            Rule::UnversionedInternalDependency => "unversioned-internal-dependency",
+            Rule::UnpinnedGitDependency => "unpinned-git-dependency",
+        }
+    }
+
+    pub(crate) fn level(self) -> Level {
+        match self {
+            Rule::UnusedWorkspaceDependency
+            | Rule::UnusedPatch
+            | Rule::UnusedReplace
+            | Rule::NonWorkspaceDependency
+            | Rule::NonWorkspaceAlternateRegistryDependency
+            | Rule::NonWorkspaceGitDependency
+            | Rule::NonWorkspacePathDependency
+            | Rule::UnusedWorkspacePackageField
+            | Rule::NonInheritedPackageField
+            | Rule::NonInheritedLints
+            | Rule::InternalPathDependency
+            | Rule::UnversionedInternalDependency
+            | Rule::UnpinnedGitDependency => Level::Error,
+            Rule::StaleIgnore
+            | Rule::VersionDrift
+            | Rule::IneffectiveDefaultFeatures
+            | Rule::RedundantFeature
+            | Rule::HoistableFeature
+            | Rule::SharedDependency
+            | Rule::SingleUseWorkspaceDependency => Level::Warning,
+        }
+    }
+
+    /// Whether findings are grouped by package rather than by manifest.
+    pub(crate) fn by_package(self) -> bool {
+        matches!(self, Rule::VersionDrift | Rule::SharedDependency)
+    }
+
+    /// Description of a finding of the rule, `subject` being the quoted offending entry.
+    fn message(self, subject: &str, detail: Option<&str>, suggestion: Option<&str>) -> String {
+        match self {
+            Rule::UnusedWorkspaceDependency => {
+                format!("{subject} is not used by any workspace member")
+            }
+            Rule::StaleIgnore => {
+                format!("{subject} is ignored but not declared in `[workspace.dependencies]`")
+            }
+            Rule::UnusedPatch => format!(
+                "{subject} of `[patch.{}]` is not applied, as listed in `[[patch.unused]]` of \
+                 `Cargo.lock`",
+                detail.unwrap_or_default()
+            ),
+            Rule::UnusedReplace => {
+                format!("{subject} of `[replace]` matches no package of `Cargo.lock`")
+            }
+            Rule::NonWorkspaceDependency
+            | Rule::NonWorkspaceAlternateRegistryDependency
+            | Rule::NonWorkspaceGitDependency
+            | Rule::NonWorkspacePathDependency => {
+                format!("{subject} does not use `workspace = true`")
+            }
+            Rule::VersionDrift => format!(
+                "{subject} requires `{}`, other manifests of the workspace require another version",
+                detail.unwrap_or_default()
+            ),
+            Rule::UnusedWorkspacePackageField => {
+                format!("{subject} is not inherited by any workspace member")
+            }
+            Rule::NonInheritedPackageField => {
+                format!("{subject} is set locally instead of inheriting `[workspace.package]`")
+            }
+            Rule::NonInheritedLints => format!("{subject} does not inherit `[workspace.lints]`"),
+            Rule::IneffectiveDefaultFeatures => format!(
+                "{subject} sets `default-features = false`, which is ignored as the workspace entry \
+                 enables default features: move it to `[workspace.dependencies]`"
+            ),
+            Rule::RedundantFeature => format!(
+                "{subject} enables feature `{}`, already enabled by `[workspace.dependencies]`",
+                detail.unwrap_or_default()
+            ),
+            Rule::HoistableFeature => format!(
+                "{subject} is used with feature `{}` by every member: enable it in \
+                 `[workspace.dependencies]`",
+                detail.unwrap_or_default()
+            ),
+            Rule::SharedDependency => match suggestion {
+                Some(suggestion) => format!(
+                    "{subject} requires `{}` and is declared by other members: add `{suggestion}` \
+                     to `[workspace.dependencies]`",
+                    detail.unwrap_or_default()
+                ),
+                None => format!(
+                    "{subject} requires `{}` and is declared by other members: add it to \
+                     `[workspace.dependencies]`",
+                    detail.unwrap_or_default()
+                ),
+            },
+            Rule::SingleUseWorkspaceDependency => format!(
+                "{subject} is only used by `{}`: declare it in its manifest instead",
+                detail.unwrap_or_default()
+            ),
+            Rule::InternalPathDependency => {
+                format!("{subject} is a workspace member and does not use `workspace = true`")
+            }
+            Rule::UnversionedInternalDependency => format!(
+                "{subject} is a workspace member declared without `version`: add `version = \"{}\"`",
+                suggestion.unwrap_or_default()
+            ),
+            Rule::UnpinnedGitDependency => match suggestion {
+                Some(suggestion) => format!(
+                    "{subject} follows a branch instead of a `rev` or `tag`: pin it with \
+                     `{suggestion}`, the commit locked in `Cargo.lock`"
+                ),
+                None => format!("{subject} follows a branch instead of a `rev` or `tag`"),
+            },
+        }
+    }
+
+    /// How to address a finding of the rule, for diagnostics.
+    pub(crate) fn help(self) -> &'static str {
+        match self {
+            Rule::UnusedWorkspaceDependency
+            | Rule::UnusedPatch
+            | Rule::UnusedReplace
+            | Rule::UnusedWorkspacePackageField => "remove this entry",
+            Rule::StaleIgnore => "remove it from `ignored`",
+            Rule::NonWorkspaceDependency
+            | Rule::NonWorkspaceAlternateRegistryDependency
+            | Rule::NonWorkspaceGitDependency
+            | Rule::NonWorkspacePathDependency
+            | Rule::SharedDependency => {
+                "declare it in `[workspace.dependencies]` and use `workspace = true`"
+            }
+            Rule::InternalPathDependency => "use `workspace = true`",
+            Rule::VersionDrift => "require the same version across the workspace",
+            Rule::NonInheritedPackageField => "use `workspace = true`",
+            Rule::NonInheritedLints => "add `[lints] workspace = true`",
+            Rule::IneffectiveDefaultFeatures => {
+                "move `default-features = false` to `[workspace.dependencies]`"
+            }
+            Rule::RedundantFeature => "remove this feature",
+            Rule::HoistableFeature => "enable the feature here and remove it from the members",
+            Rule::SingleUseWorkspaceDependency => "declare it in the manifest of its member",
+            Rule::UnversionedInternalDependency => "add the `version` of the member",
+            Rule::UnpinnedGitDependency => "pin it with a `rev` or `tag`",
+        }
+    }
+
+    /// Title of the section listing the findings of the rule.
+    pub(crate) fn title(self) -> &'static str {
+        match self {
+            Rule::UnusedWorkspaceDependency => "Unused workspace dependencies",
+            Rule::StaleIgnore => "Stale ignored workspace dependencies",
+            Rule::UnusedPatch => "Unused patches",
+            Rule::UnusedReplace => "Unused replacements",
+            Rule::NonWorkspaceDependency => "Non workspace dependencies",
+            Rule::NonWorkspaceAlternateRegistryDependency => {
+                "Non workspace alternate registry dependencies"
+            }
+            Rule::NonWorkspaceGitDependency => "Non workspace git dependencies",
+            Rule::NonWorkspacePathDependency => "Non workspace path dependencies",
+            Rule::VersionDrift => "Version drift",
+            Rule::UnusedWorkspacePackageField => "Unused workspace package fields",
+            Rule::NonInheritedPackageField => "Non inherited package fields",
+            Rule::NonInheritedLints => "Non inherited lints",
+            Rule::IneffectiveDefaultFeatures => "Ineffective default-features",
+            Rule::RedundantFeature => "Redundant features",
+            Rule::HoistableFeature => "Features enabled by every member",
+            Rule::SharedDependency => "Dependencies shared by several members",
+            Rule::SingleUseWorkspaceDependency => "Workspace dependencies used by a single member",
+            Rule::InternalPathDependency => "Member path dependencies",
+            Rule::UnversionedInternalDependency => "Unversioned member workspace dependencies",
+            Rule::UnpinnedGitDependency => "Unpinned git dependencies",
+        }
+    }
+
+    /// Message printed when the rule has no finding.
+    pub(crate) fn success_message(self) -> &'static str {
+        match self {
+            Rule::UnusedWorkspaceDependency => "No unused workspace dependencies",
+            Rule::StaleIgnore => "No stale ignored workspace dependencies",
+            Rule::UnusedPatch => "No unused patches",
+            Rule::UnusedReplace => "No unused replacements",
+            Rule::NonWorkspaceDependency => "No non workspace dependencies",
+            Rule::NonWorkspaceAlternateRegistryDependency => {
+                "No non workspace alternate registry dependencies"
+            }
+            Rule::NonWorkspaceGitDependency => "No non workspace git dependencies",
+            Rule::NonWorkspacePathDependency => "No non workspace path dependencies",
+            Rule::VersionDrift => "No version drift",
+            Rule::UnusedWorkspacePackageField => "No unused workspace package fields",
+            Rule::NonInheritedPackageField => "No non inherited package fields",
+            Rule::NonInheritedLints => "No non inherited lints",
+            Rule::IneffectiveDefaultFeatures => "No ineffective default-features",
+            Rule::RedundantFeature => "No redundant features",
+            Rule::HoistableFeature => "No features enabled by every member",
+            Rule::SharedDependency => "No dependencies shared by several members",
+            Rule::SingleUseWorkspaceDependency => {
+                "No workspace dependencies used by a single member"
+            }
+            Rule::InternalPathDependency => "No member path dependencies",
+            Rule::UnversionedInternalDependency => "No unversioned member workspace dependencies",
+            Rule::UnpinnedGitDependency => "No unpinned git dependencies",
+        }
+    }
+}
+
+impl Serialize for Rule {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.id())
+    }
+}
+
+/// An issue found in a manifest.
+#[derive(Clone, Debug, Serialize)]
+pub(crate) struct Finding {
+    pub(crate) rule: Rule,
+    /// manifest where the issue is located
+    pub(crate) manifest: PathBuf,
+    /// key of the offending entry
+    pub(crate) name: String,
+    /// package name, when the entry is renamed with `package = "..."`
+    pub(crate) package: Option<String>,
+    /// rule specific value of the entry, ie the version requirement
+    pub(crate) detail: Option<String>,
+    /// proposed replacement, ie the entry to add to `[workspace.dependencies]`
+    pub(crate) suggestion: Option<String>,
+    /// position of the offending key in the manifest
+    pub(crate) location: Option<Location>,
+}
+
+impl Finding {
+    pub(crate) fn new(rule: Rule, manifest: &Path, name: impl Into<String>) -> Self {
+        Self {
+            rule,
+            manifest: manifest.to_path_buf(),
+            name: name.into(),
+            package: None,
+            detail: None,
+            suggestion: None,
+            location: None,
+        }
+    }
+
+    /// Set the package name of a renamed entry, ignored when it matches the key.
+    pub(crate) fn package(mut self, package: Option<impl Into<String>>) -> Self {
+        self.package = package.map(Into::into).filter(|e| *e != self.name);
+        self
+    }
+
+    pub(crate) fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub(crate) fn suggestion(mut self, suggestion: Option<impl Into<String>>) -> Self {
+        self.suggestion = suggestion.map(Into::into);
+        self
+    }
+
+    pub(crate) fn location(mut self, location: Option<Location>) -> Self {
+        self.location = location;
+        self
+    }
+
+    /// Name of the package of the entry, renamed or not.
+    pub(crate) fn package_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+
+    /// Entry as displayed in text reports, ie `serde1 (package = "serde"): 1.0`.
+    pub(crate) fn label(&self) -> String {
+        let label = match &self.package {
+            Some(package) => format!("{} (package = \"{package}\")", self.name),
+            None => self.name.clone(),
+        };
+        match &self.detail {
+            Some(detail) => format!("{label}: {detail}"),
+            None => label,
+        }
+    }
+
+    /// Manifest with the position of the entry, ie `crate1/Cargo.toml:8:1`, as understood by editors.
+    pub(crate) fn position(&self) -> String {
+        match self.location {
+            Some(Location { line, column }) => {
+                format!("{}:{line}:{column}", self.manifest.display())
+            }
+            None => self.manifest.display().to_string(),
+        }
+    }
+
+    /// Description of the finding, for reports without section per rule.
+    pub(crate) fn message(&self) -> String {
+        let subject = match &self.package {
+            Some(package) => format!("`{}` (package `{package}`)", self.name),
+            None => format!("`{}`", self.name),
+        };
+        self.rule
+            .message(&subject, self.detail.as_deref(), self.suggestion.as_deref())
+    }
+}
+
+/// Result of the checks on a workspace.
+#[derive(Clone, Debug)]
+pub(crate) struct Report {
+    /// root `Cargo.toml` of the workspace
+    pub(crate) root_manifest: PathBuf,
+    /// rules that were checked, in reporting order
+    pub(crate) rules: Vec<Rule>,
+    pub(crate) findings: Vec<Finding>,
+    /// contents of the manifests as checked, quoted by diagnostics even once fixed
+    pub(crate) sources: BTreeMap<PathBuf, String>,
+}
+
+impl Report {
+    pub(crate) fn new(root_manifest: &Path) -> Self {
+        Self {
+            root_manifest: root_manifest.to_path_buf(),
+            rules: vec![],
+            findings: vec![],
+            sources: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn add_source(&mut self, manifest: &Path, contents: &str) {
+        self.sources
+            .insert(manifest.to_path_buf(), contents.to_owned());
+    }
+
+    pub(crate) fn check(&mut self, rule: Rule) {
+        if !self.rules.contains(&rule) {
+            self.rules.push(rule);
+        }
+    }
+
+    pub(crate) fn push(&mut self, finding: Finding) {
+        self.findings.push(finding);
+    }
+
+    pub(crate) fn findings(&self, rule: Rule) -> impl Iterator<Item = &Finding> {
+        self.findings.iter().filter(move |e| e.rule == rule)
+    }
+
+    /// Findings ordered by rule then manifest, keeping the discovery order within a manifest.
+    ///
+    /// Findings of rules grouped by package are ordered by package first.
+    pub(crate) fn sorted_findings(&self) -> Vec<&Finding> {
+        let mut findings: Vec<_> = self.findings.iter().collect();
+        findings.sort_by_key(|e| {
+            let rule = self.rules.iter().position(|rule| *rule == e.rule);
+            let package = e.rule.by_package().then(|| e.package_name());
+            (rule, package, &e.manifest)
+        });
+        findings
+    }
+
+    pub(crate) fn has_findings(&self) -> bool {
+        !self.findings.is_empty()
+    }
+
+    /// Process exit code matching the report.
+    pub(crate) fn exit_code(&self) -> i32 {
+        if self.has_findings() { 1 } else { 0 }
+    }
+}